@@ -0,0 +1,144 @@
+//! Registers every day module, so that adding a day is just adding its file and declaring it.
+//!
+//! Days for the default year live directly in `src/` (`src/day01.rs`) and are declared in
+//! `main.rs`, and days for other years in a directory per year (`src/year2024/day01.rs`) declared
+//! in that directory's `mod.rs`. The declarations are written out in the source rather than
+//! generated, so that `cargo fmt` sees every day. This generates `$OUT_DIR/days.rs`, which is
+//! included by `main.rs` and contains `register_days`, which adds each day's solver to the
+//! registry. A day can also offer alternative
+//! solvers by defining `pub const ALTERNATIVES: &[(&str, &(dyn Aoc + Send + Sync))]`, a generator
+//! of random inputs by defining `pub fn generate(rng: &mut Rng, size: usize) -> String`, a
+//! validator of inputs by defining `pub fn validate(input: &str) -> Vec<Violation>`, and a
+//! visualizer by defining `pub fn visualize(input: &str) -> Result<Visualization, AocError>`.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+/// The year of the days directly in `src/`
+const DEFAULT_YEAR: u16 = 2025;
+
+struct Day {
+    year: u16,
+    day: usize,
+    /// Two digit day number, as used in the module name
+    number: String,
+    has_alternatives: bool,
+    has_generator: bool,
+    has_validator: bool,
+    has_visualizer: bool,
+}
+
+impl Day {
+    /// Path to the module from the crate root
+    fn module(&self) -> String {
+        if self.year == DEFAULT_YEAR {
+            format!("day{}", self.number)
+        } else {
+            format!("year{}::day{}", self.year, self.number)
+        }
+    }
+}
+
+fn main() {
+    let src_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("src");
+    println!("cargo::rerun-if-changed={}", src_dir.display());
+
+    let mut days = find_days(&src_dir, DEFAULT_YEAR);
+    for entry in fs::read_dir(&src_dir).expect("could not read src directory") {
+        let entry = entry.unwrap();
+        let year = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.strip_prefix("year"))
+            .and_then(|year| year.parse().ok());
+        if let Some(year) = year
+            && entry.path().is_dir()
+        {
+            days.extend(find_days(&entry.path(), year));
+        }
+    }
+    days.sort_by_key(|day| (day.year, day.day));
+
+    let mut out = String::from("// Generated by build.rs from the day modules in src/\n\n");
+    writeln!(
+        out,
+        "/// The year of the days that aren't in a `yearYYYY` directory"
+    )
+    .unwrap();
+    writeln!(out, "const DEFAULT_YEAR: u16 = {DEFAULT_YEAR};\n").unwrap();
+
+    writeln!(out, "/// Add every day's solvers to the registry").unwrap();
+    writeln!(
+        out,
+        "fn register_days(registry: &mut registry::Registry) {{"
+    )
+    .unwrap();
+    for day in &days {
+        let Day { year, day: n, .. } = day;
+        let module = day.module();
+        writeln!(
+            out,
+            "    registry.register({year}, {n}, registry::DEFAULT_SOLVER, &{module}::Day{});",
+            day.number
+        )
+        .unwrap();
+        if day.has_alternatives {
+            writeln!(
+                out,
+                "    for &(name, aoc) in {module}::ALTERNATIVES {{\n        \
+                 registry.register({year}, {n}, name, aoc);\n    }}"
+            )
+            .unwrap();
+        }
+        if day.has_generator {
+            writeln!(
+                out,
+                "    registry.register_generator({year}, {n}, {module}::generate);"
+            )
+            .unwrap();
+        }
+        if day.has_validator {
+            writeln!(
+                out,
+                "    registry.register_validator({year}, {n}, {module}::validate);"
+            )
+            .unwrap();
+        }
+        if day.has_visualizer {
+            writeln!(
+                out,
+                "    registry.register_visualizer({year}, {n}, {module}::visualize);"
+            )
+            .unwrap();
+        }
+    }
+    writeln!(out, "}}").unwrap();
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("days.rs");
+    fs::write(out_path, out).expect("could not write days.rs");
+}
+
+/// Find the `dayNN.rs` modules in `dir`
+fn find_days(dir: &Path, year: u16) -> Vec<Day> {
+    fs::read_dir(dir)
+        .unwrap_or_else(|e| panic!("could not read {}: {e}", dir.display()))
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let number = name.strip_prefix("day")?.strip_suffix(".rs")?;
+            if number.len() != 2 || !number.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+
+            let source = fs::read_to_string(&path).ok()?;
+            Some(Day {
+                year,
+                day: number.parse().ok()?,
+                number: number.to_string(),
+                has_alternatives: source.contains("pub const ALTERNATIVES"),
+                has_generator: source.contains("pub fn generate("),
+                has_validator: source.contains("pub fn validate("),
+                has_visualizer: source.contains("pub fn visualize("),
+            })
+        })
+        .collect()
+}
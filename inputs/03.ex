@@ -0,0 +1,4 @@
+987654321111111
+811111111111119
+234234234234278
+818181911112111
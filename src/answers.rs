@@ -0,0 +1,244 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    io::{self, ErrorKind},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{Answer, Part, error::AocError};
+
+/// The known correct answers for an input, stored alongside it (e.g. `inputs/01.ans`), along with
+/// any parameters the solver needs for that input:
+///
+/// ```text
+/// part1: 40
+/// part2: 25272
+/// merge_count: 10
+/// ```
+///
+/// Either part may be missing if its answer isn't known yet. Any other key is a parameter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    pub params: Params,
+}
+
+impl Answers {
+    /// Load the answers at `path`, or no answers if the file doesn't exist
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(s) => s.parse(),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+
+    /// Record `answer` as the correct answer for `part`. Parts that aren't implemented yet are
+    /// left unknown.
+    pub fn record(&mut self, part: Part, answer: &Answer) {
+        if *answer == Answer::NotImplemented {
+            return;
+        }
+
+        let slot = match part {
+            Part::One => &mut self.part1,
+            Part::Two => &mut self.part2,
+        };
+        *slot = Some(answer.to_string());
+    }
+
+    /// Check an answer against the known correct answer
+    pub fn verify(&self, part: Part, answer: &Answer) -> Verification {
+        match self.get(part) {
+            _ if *answer == Answer::NotImplemented => Verification::Unknown,
+            None => Verification::Unknown,
+            Some(expected) if expected == answer.to_string() => Verification::Pass,
+            Some(expected) => Verification::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+}
+
+impl FromStr for Answers {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Self::default();
+
+        for (i, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let invalid = |reason: &str| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("answers line {}: {reason}: {line:?}", i + 1),
+                )
+            };
+
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| invalid("expected `<key>: <value>`"))?;
+            let value = value.trim().to_string();
+            match key.trim() {
+                "part1" => answers.part1 = Some(value),
+                "part2" => answers.part2 = Some(value),
+                "" => return Err(invalid("missing key")),
+                param => {
+                    answers.params.0.insert(param.to_string(), value);
+                }
+            }
+        }
+
+        Ok(answers)
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(part1) = &self.part1 {
+            writeln!(f, "part1: {part1}")?;
+        }
+        if let Some(part2) = &self.part2 {
+            writeln!(f, "part2: {part2}")?;
+        }
+        for (name, value) in &self.params.0 {
+            writeln!(f, "{name}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parameters for a solver that differ between inputs, e.g. the examples often use smaller numbers
+/// than the real input
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(BTreeMap<String, String>);
+
+impl Params {
+    /// The value of a parameter, or None if it isn't given
+    pub fn get<T>(&self, name: &str) -> Result<Option<T>, AocError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.0
+            .get(name)
+            .map(|value| value.parse().map_err(|e| AocError::param(name, e)))
+            .transpose()
+    }
+
+    /// Check that there are no parameters other than the given ones, to catch typos
+    pub fn check_known(&self, known: &[&str]) -> Result<(), AocError> {
+        match self.0.keys().find(|name| !known.contains(&name.as_str())) {
+            Some(name) => Err(AocError::param(name, "unknown parameter for this day")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Result of checking an answer against the known correct answer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Verification {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// There is no known answer to check against
+    #[default]
+    Unknown,
+}
+
+impl Verification {
+    /// Lowercase name of the status, for machine-readable output
+    pub fn status(&self) -> &'static str {
+        match self {
+            Verification::Pass => "pass",
+            Verification::Fail { .. } => "fail",
+            Verification::Unknown => "unknown",
+        }
+    }
+}
+
+impl Display for Verification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Verification::Pass => "pass",
+            Verification::Fail { .. } => "FAIL",
+            Verification::Unknown => "unknown",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let answers = Answers {
+            part1: Some("1227775554".to_string()),
+            part2: None,
+            params: Params::default(),
+        };
+        assert_eq!("part1: 1227775554\n", answers.to_string());
+        assert_eq!(answers, answers.to_string().parse().unwrap());
+    }
+
+    #[test]
+    fn test_params() {
+        let answers: Answers = "part1: 40\nmerge_count: 10\n".parse().unwrap();
+        assert_eq!(
+            Some(10),
+            answers.params.get::<usize>("merge_count").unwrap()
+        );
+        assert_eq!(None, answers.params.get::<usize>("other").unwrap());
+        assert!(answers.params.check_known(&["merge_count"]).is_ok());
+        assert!(answers.params.check_known(&[]).is_err());
+        assert!(answers.to_string().contains("merge_count: 10"));
+
+        let answers: Answers = "merge_count: ten\n".parse().unwrap();
+        assert!(answers.params.get::<usize>("merge_count").is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut answers: Answers = "part1: 357\n".parse().unwrap();
+        assert_eq!(
+            Verification::Pass,
+            answers.verify(Part::One, &Answer::UInt(357))
+        );
+        assert_eq!(
+            Verification::Fail {
+                expected: "357".to_string()
+            },
+            answers.verify(Part::One, &Answer::Int(-357))
+        );
+        assert_eq!(
+            Verification::Unknown,
+            answers.verify(Part::Two, &Answer::UInt(1))
+        );
+
+        answers.record(Part::Two, &Answer::NotImplemented);
+        assert_eq!(None, answers.part2);
+        answers.record(Part::Two, &Answer::Text("abc".to_string()));
+        assert_eq!(
+            Verification::Pass,
+            answers.verify(Part::Two, &Answer::Text("abc".to_string()))
+        );
+    }
+}
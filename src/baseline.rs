@@ -0,0 +1,213 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, ErrorKind},
+    path::Path,
+    time::Duration,
+};
+
+use crate::{Stage, bench::Stats};
+
+/// Benchmark results from a previous run, keyed by year, day, solver and stage, so that results
+/// for different years or solvers are never compared with each other
+///
+/// Stored as CSV with one row per solver and stage, and all times in nanoseconds:
+///
+/// ```text
+/// year,day,solver,stage,runs,min_ns,median_ns,mean_ns,p95_ns,stddev_ns
+/// 2025,1,default,parse,1000,4100,4300,4410,5000,210
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Baseline(BTreeMap<Key, Record>);
+
+/// Which solver and stage a [`Record`] is for
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Key {
+    pub year: u16,
+    pub day: usize,
+    pub solver: String,
+    pub stage: Stage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Record {
+    pub runs: usize,
+    pub stats: Stats,
+}
+
+const HEADER: &str = "year,day,solver,stage,runs,min_ns,median_ns,mean_ns,p95_ns,stddev_ns";
+
+impl Baseline {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Load the baseline at `path`, or an empty baseline if the file doesn't exist yet
+    pub fn load_or_default(path: &Path) -> io::Result<Self> {
+        match Self::load(path) {
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            result => result,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, key: &Key) -> Option<&Record> {
+        self.0.get(key)
+    }
+
+    pub fn insert(&mut self, key: Key, record: Record) {
+        self.0.insert(key, record);
+    }
+
+    /// Add all records from `other`, replacing any existing records for the same key
+    pub fn extend(&mut self, other: Baseline) {
+        self.0.extend(other.0);
+    }
+}
+
+impl std::str::FromStr for Baseline {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |line: usize, reason: &str| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("baseline line {line}: {reason}"),
+            )
+        };
+
+        let mut lines = s.lines().enumerate();
+        if lines
+            .next()
+            .is_none_or(|(_, header)| header.trim() != HEADER)
+        {
+            return Err(invalid(1, "expected the header line"));
+        }
+
+        let mut baseline = Self::default();
+        for (i, line) in lines.filter(|(_, line)| !line.trim().is_empty()) {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [year, day, solver, stage, runs, times @ ..] = fields.as_slice() else {
+                return Err(invalid(i + 1, "not enough fields"));
+            };
+            let [min, median, mean, p95, stddev] = times else {
+                return Err(invalid(i + 1, "expected 10 fields"));
+            };
+
+            let nanos = |s: &str| {
+                s.parse()
+                    .map(Duration::from_nanos)
+                    .map_err(|_| invalid(i + 1, "invalid time"))
+            };
+
+            let record = Record {
+                runs: runs.parse().map_err(|_| invalid(i + 1, "invalid runs"))?,
+                stats: Stats {
+                    min: nanos(min)?,
+                    median: nanos(median)?,
+                    mean: nanos(mean)?,
+                    p95: nanos(p95)?,
+                    stddev: nanos(stddev)?,
+                },
+            };
+            let key = Key {
+                year: year.parse().map_err(|_| invalid(i + 1, "invalid year"))?,
+                day: day.parse().map_err(|_| invalid(i + 1, "invalid day"))?,
+                solver: solver.to_string(),
+                stage: stage.parse().map_err(|_| invalid(i + 1, "invalid stage"))?,
+            };
+            baseline.insert(key, record);
+        }
+
+        Ok(baseline)
+    }
+}
+
+impl std::fmt::Display for Baseline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        for (key, Record { runs, stats }) in self.0.iter() {
+            let Key {
+                year,
+                day,
+                solver,
+                stage,
+            } = key;
+            writeln!(
+                f,
+                "{year},{day},{solver},{stage},{runs},{},{},{},{},{}",
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+                stats.p95.as_nanos(),
+                stats.stddev.as_nanos()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Percentage change in median time from the baseline to the current record. Positive is slower.
+pub fn percent_change(baseline: &Record, current: &Record) -> f64 {
+    let before = baseline.stats.median.as_secs_f64();
+    let after = current.stats.median.as_secs_f64();
+    (after - before) / before * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::DEFAULT_SOLVER;
+
+    fn record(median_us: u64) -> Record {
+        let median = Duration::from_micros(median_us);
+        Record {
+            runs: 10,
+            stats: Stats {
+                min: median / 2,
+                median,
+                mean: median,
+                p95: median * 2,
+                stddev: Duration::from_nanos(123),
+            },
+        }
+    }
+
+    fn key(year: u16, day: usize, solver: &str, stage: Stage) -> Key {
+        Key {
+            year,
+            day,
+            solver: solver.to_string(),
+            stage,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut baseline = Baseline::default();
+        baseline.insert(key(2025, 1, DEFAULT_SOLVER, Stage::Parse), record(5));
+        baseline.insert(key(2025, 1, DEFAULT_SOLVER, Stage::Total), record(20));
+        baseline.insert(key(2025, 12, DEFAULT_SOLVER, Stage::Pt2), record(1000));
+        baseline.insert(key(2025, 12, "naive", Stage::Pt2), record(9000));
+        baseline.insert(key(2024, 12, DEFAULT_SOLVER, Stage::Pt2), record(7));
+
+        let parsed: Baseline = baseline.to_string().parse().unwrap();
+        assert_eq!(baseline, parsed);
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let err = format!("{HEADER}\n1,parse,10,1,2,3\n")
+            .parse::<Baseline>()
+            .unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_percent_change() {
+        assert!((percent_change(&record(100), &record(150)) - 50.0).abs() < 1e-9);
+        assert!((percent_change(&record(100), &record(75)) + 25.0).abs() < 1e-9);
+    }
+}
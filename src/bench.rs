@@ -0,0 +1,301 @@
+use std::{
+    fmt::Display,
+    path::PathBuf,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    DayArgs, Stage,
+    baseline::{Baseline, Key, Record, percent_change},
+    load_answers, memory,
+    registry::DEFAULT_SOLVER,
+    run_with_timing,
+};
+
+#[derive(clap::Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub days: DayArgs,
+
+    /// Number of measured runs per day
+    #[arg(short = 'n', long)]
+    iterations: Option<usize>,
+
+    /// Time budget per day in seconds, used when --iterations is omitted (or as an upper limit
+    /// when both are given)
+    #[arg(short, long, value_parser = parse_seconds)]
+    time: Option<Duration>,
+
+    /// Number of runs per day to discard before measuring
+    #[arg(long, default_value_t = 3)]
+    warmup: usize,
+
+    /// Save the results to this baseline file (CSV), updating any days already in it
+    #[arg(long)]
+    save: Option<PathBuf>,
+
+    /// Compare the median times against this baseline file
+    #[arg(long)]
+    compare: Option<PathBuf>,
+
+    /// Percentage increase in a day's median total time, compared to the baseline, that counts as a
+    /// regression
+    #[arg(long, default_value_t = 10.0, requires = "compare")]
+    threshold: f64,
+
+    /// Count the allocations made by each stage, and show how many there were, their total size
+    /// and the peak memory allocated at once
+    #[arg(long)]
+    allocs: bool,
+}
+
+/// A non-negative, finite number of seconds
+pub fn parse_seconds(s: &str) -> Result<Duration, String> {
+    let secs: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    Duration::try_from_secs_f64(secs).map_err(|_| format!("{s} isn't a number of seconds"))
+}
+
+/// Time budget per day if neither the number of iterations nor a time is given
+const DEFAULT_TIME_BUDGET: Duration = Duration::from_secs(1);
+
+pub fn run(args: &BenchArgs) -> ExitCode {
+    let source = args.days.source();
+    let parts = args.days.parts();
+
+    let time_budget = match (args.iterations, args.time) {
+        (_, Some(time)) => Some(time),
+        (None, None) => Some(DEFAULT_TIME_BUDGET),
+        (Some(_), None) => None,
+    };
+
+    let days = match args.days.days() {
+        Ok(days) => days,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let compare_to = match args.compare.as_deref().map(Baseline::load).transpose() {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            eprintln!("error: could not load baseline: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let solver = args.days.solver.as_deref().unwrap_or(DEFAULT_SOLVER);
+    let mut results = Baseline::default();
+    if args.allocs {
+        memory::enable();
+    }
+
+    print!(
+        "{:>3} {:5} {:>12} {:>12} {:>12} {:>12} {:>12} {:>10}",
+        "day", "stage", "min (us)", "median (us)", "mean (us)", "p95 (us)", "stddev (us)", "runs"
+    );
+    if args.allocs {
+        print!(
+            " {:>10} {:>12} {:>12}",
+            "allocs", "alloc bytes", "peak bytes"
+        );
+    }
+    if compare_to.is_some() {
+        print!(" {:>10}", "change");
+    }
+    println!();
+
+    let mut any_failed = false;
+    let mut regressions = Vec::new();
+    for (day, aoc) in days {
+        let loaded = source
+            .read(day)
+            .and_then(|input| Ok((input, load_answers(day, &source)?.params)));
+        let (input, params) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                println!("{day:>3} error: {}", e.kind());
+                any_failed = true;
+                continue;
+            }
+        };
+
+        for _ in 0..args.warmup {
+            // Errors will be reported by the measured runs below
+            let _ = run_with_timing(aoc, &input, &params, &parts);
+        }
+
+        let mut samples = Vec::new();
+        // Allocations are the same on every run, so only the last run's are kept
+        let mut usage = memory::StageUsage::default();
+        let start = Instant::now();
+        let result = loop {
+            match run_with_timing(aoc, &input, &params, &parts) {
+                Ok(solution) => {
+                    samples.push(solution.times());
+                    usage = solution.usage;
+                }
+                Err(e) => break Err(e),
+            }
+
+            let done_iterations = args.iterations.is_some_and(|n| samples.len() >= n);
+            let out_of_time = time_budget.is_some_and(|budget| start.elapsed() >= budget);
+            if done_iterations || out_of_time {
+                break Ok(());
+            }
+        };
+
+        if let Err(e) = result {
+            println!("{day:>3} error: {}", e.kind());
+            any_failed = true;
+            continue;
+        }
+
+        for stage in Stage::measured(&parts) {
+            let key = Key {
+                year: args.days.year,
+                day,
+                solver: solver.to_string(),
+                stage,
+            };
+            let record = Record {
+                runs: samples.len(),
+                stats: Stats::new(samples.iter().map(|times| times.get(stage)).collect()),
+            };
+            print!("{day:>3} {stage:5} {} {:>10}", record.stats, record.runs);
+            if args.allocs {
+                let usage = usage.get(stage);
+                print!(
+                    " {:>10} {:>12} {:>12}",
+                    usage.allocations, usage.bytes, usage.peak
+                );
+            }
+
+            if let Some(baseline) = &compare_to {
+                match baseline.get(&key) {
+                    Some(before) => {
+                        let change = percent_change(before, &record);
+                        print!(" {:>+9.1}%", change);
+                        if stage == Stage::Total && change > args.threshold {
+                            print!(" REGRESSION");
+                            regressions.push(day);
+                        }
+                    }
+                    None => print!(" {:>10}", "-"),
+                }
+            }
+            println!();
+
+            results.insert(key, record);
+        }
+    }
+
+    if let Some(path) = &args.save {
+        let saved = Baseline::load_or_default(path).and_then(|mut baseline| {
+            baseline.extend(results);
+            baseline.save(path)
+        });
+        if let Err(e) = saved {
+            eprintln!("error: could not save baseline to {}: {e}", path.display());
+            any_failed = true;
+        }
+    }
+
+    if !regressions.is_empty() {
+        eprintln!(
+            "regressed by more than {}% against the baseline: days {}",
+            args.threshold,
+            regressions
+                .iter()
+                .map(|day| day.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    if any_failed || !regressions.is_empty() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Summary statistics over a set of timing samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Calculate statistics over the samples, which must not be empty
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "need at least one sample");
+        samples.sort_unstable();
+
+        let n = samples.len();
+        let mean_secs = samples.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        let variance = samples
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean_secs).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+
+        Self {
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean_secs),
+            // Nearest-rank percentile
+            p95: samples[(n * 95).div_ceil(100) - 1],
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let us = |d: Duration| d.as_secs_f64() * 1e6;
+        write!(
+            f,
+            "{:>12.1} {:>12.1} {:>12.1} {:>12.1} {:>12.1}",
+            us(self.min),
+            us(self.median),
+            us(self.mean),
+            us(self.p95),
+            us(self.stddev)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<Duration> = [5, 1, 4, 2, 3].map(Duration::from_micros).to_vec();
+        let stats = Stats::new(samples);
+        assert_eq!(Duration::from_micros(1), stats.min);
+        assert_eq!(Duration::from_micros(3), stats.median);
+        assert_eq!(Duration::from_micros(3), stats.mean);
+        assert_eq!(Duration::from_micros(5), stats.p95);
+        assert!((stats.stddev.as_secs_f64() * 1e6 - 2f64.sqrt()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_stats_even_count_and_p95() {
+        let samples: Vec<Duration> = (1..=100).map(Duration::from_micros).collect();
+        let stats = Stats::new(samples);
+        assert_eq!(Duration::from_nanos(50_500), stats.median);
+        assert_eq!(Duration::from_micros(95), stats.p95);
+    }
+}
@@ -1,54 +1,129 @@
-use crate::Aoc;
+use std::str::FromStr;
 
-const INPUT: &str = include_str!("../inputs/01.in");
+use crate::{
+    Answer, Solution,
+    error::{AocError, parse_lines, parse_num},
+    generate::Rng,
+    validate::{self, Violation},
+};
 
 pub struct Day01;
 
-impl Aoc for Day01 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+impl Solution for Day01 {
+    type Parsed = Vec<Rotation>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_lines(input)
+    }
+
+    fn part1(&self, rotations: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(rotations).into())
+    }
+
+    fn part2(&self, rotations: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part2(rotations).into())
     }
 }
 
-fn run_on_input(input: &str) -> (usize, usize) {
+fn part1(rotations: &[Rotation]) -> usize {
     let mut pointing_at = 50;
-    let mut pt2 = 0;
 
-    let pt1 = input
-        .trim()
-        .lines()
-        .map(|line| {
-            let (direction, amount) = line.split_at(1);
-            let amount: i64 = amount.parse().unwrap();
-
-            // If we are already at zero and rotate left, div_euclid would count us as crossing zero
-            // but we have already counted this in the previous step.
-            if direction == "L" && pointing_at == 0 {
-                pt2 -= 1;
+    rotations
+        .iter()
+        .map(|rotation| {
+            if rotation.left {
+                pointing_at -= rotation.amount;
+            } else {
+                pointing_at += rotation.amount;
             }
 
-            match direction {
-                "R" => pointing_at += amount,
-                "L" => pointing_at -= amount,
-                _ => panic!("should only be L or R as first character"),
-            };
-
-            let zero_crossings = pointing_at.div_euclid(100).abs();
             pointing_at = pointing_at.rem_euclid(100);
-            pt2 += zero_crossings as usize;
-
-            // Handle special case where we land at zero without crossing it
-            if direction == "L" && pointing_at == 0 {
-                pt2 += 1;
-            }
-
             pointing_at
         })
         .filter(|n| *n == 0)
-        .count();
+        .count()
+}
+
+fn part2(rotations: &[Rotation]) -> usize {
+    let mut pointing_at: i64 = 50;
+    let mut pt2 = 0;
+
+    for rotation in rotations {
+        // If we are already at zero and rotate left, div_euclid would count us as crossing zero
+        // but we have already counted this in the previous step.
+        if rotation.left && pointing_at == 0 {
+            pt2 -= 1;
+        }
+
+        if rotation.left {
+            pointing_at -= rotation.amount;
+        } else {
+            pointing_at += rotation.amount;
+        }
+
+        let zero_crossings = pointing_at.div_euclid(100).abs();
+        pointing_at = pointing_at.rem_euclid(100);
+        pt2 += zero_crossings as usize;
+
+        // Handle special case where we land at zero without crossing it
+        if rotation.left && pointing_at == 0 {
+            pt2 += 1;
+        }
+    }
 
-    (pt1, pt2)
+    pt2
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Rotation {
+    left: bool,
+    amount: i64,
+}
+
+impl FromStr for Rotation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let left = match s.get(..1) {
+            Some("L") => true,
+            Some("R") => false,
+            _ => return Err("should only be L or R as first character".to_string()),
+        };
+        let amount = parse_num(&s[1..], "rotation amount")?;
+        Ok(Rotation { left, amount })
+    }
+}
+
+/// Check that every line is a rotation like `L68`
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if input.trim().is_empty() {
+        violations.push(Violation::line(1, "input is empty"));
+    }
+    for (n, line) in validate::lines(input) {
+        match line.chars().next() {
+            Some('L' | 'R') => {
+                validate::number::<i64>(&mut violations, n, line, &line[1..], "rotation amount");
+            }
+            Some(c) => violations.push(Violation::at(
+                n,
+                1,
+                format!("expected 'L' or 'R', found {c:?}"),
+            )),
+            None => violations.push(Violation::line(n, "blank line")),
+        }
+    }
+    violations
+}
+
+/// `size` random rotations
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let direction = if rng.chance(1, 2) { 'L' } else { 'R' };
+            format!("{direction}{}\n", rng.range(1..=999))
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -59,30 +134,47 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let (pt1, pt2) = run_on_input(EXAMPLE_INPUT);
-        assert_eq!(3, pt1);
-        assert_eq!(6, pt2);
+        let rotations = Day01.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(3, part1(&rotations));
+        assert_eq!(6, part2(&rotations));
     }
 
     #[test]
     fn test_multiple_crossings() {
         let input = "R1000";
-        let (_, pt2) = run_on_input(input);
+        let pt2 = part2(&Day01.parse(input).unwrap());
         assert_eq!(10, pt2);
 
         let input = "L1000";
-        let (_, pt2) = run_on_input(input);
+        let pt2 = part2(&Day01.parse(input).unwrap());
         assert_eq!(10, pt2);
     }
 
     #[test]
     fn test_correctly_handles_left_rotate_to_exactly_0() {
         let input = "L50";
-        let (_, pt2) = run_on_input(input);
+        let pt2 = part2(&Day01.parse(input).unwrap());
         assert_eq!(1, pt2);
 
         let input = "L150";
-        let (_, pt2) = run_on_input(input);
+        let pt2 = part2(&Day01.parse(input).unwrap());
         assert_eq!(2, pt2);
     }
+
+    #[test]
+    fn test_validate() {
+        let violations: Vec<String> = validate("L68\nX5\nR\n\nR1a\n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 2, column 1: expected 'L' or 'R', found 'X'",
+                "line 3, column 2: missing rotation amount",
+                "line 4: blank line",
+                "line 5, column 3: unexpected 'a' in rotation amount \"1a\"",
+            ],
+            violations
+        );
+    }
 }
@@ -1,29 +1,60 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, str::FromStr};
 
-use crate::Aoc;
-
-const INPUT: &str = include_str!("../inputs/02.in");
+use crate::{
+    Answer, Solution,
+    error::{AocError, parse_num},
+    generate::Rng,
+    validate::{self, Violation},
+};
 
 pub struct Day02;
 
-impl Aoc for Day02 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+impl Solution for Day02 {
+    type Parsed = Vec<IdRange>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        input
+            .trim()
+            .split(',')
+            .map(|range| {
+                // The whole input is on a single line
+                range.parse().map_err(|e| AocError::parse(1, range, e))
+            })
+            .collect()
+    }
+
+    fn part1(&self, ranges: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(ranges).into())
+    }
+
+    fn part2(&self, ranges: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part2(ranges).into())
     }
 }
 
-fn run_on_input(input: &str) -> (usize, usize) {
-    let mut pt1 = 0;
-    let mut pt2 = 0;
+#[derive(Debug, Clone, Copy)]
+pub struct IdRange {
+    start: usize,
+    end: usize,
+}
 
-    for range in input.trim().split(',') {
-        let (a, b) = range.split_once('-').unwrap();
-        let an: usize = a.parse().unwrap();
-        let bn: usize = b.parse().unwrap();
+impl FromStr for IdRange {
+    type Err = String;
 
-        // Part 1
-        let start_at: usize = a.split_at(a.len() / 2).0.parse().unwrap_or(1);
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (a, b) = s.split_once('-').ok_or("expected a range like 11-22")?;
+        Ok(IdRange {
+            start: parse_num(a, "range start")?,
+            end: parse_num(b, "range end")?,
+        })
+    }
+}
+
+fn part1(ranges: &[IdRange]) -> usize {
+    let mut pt1 = 0;
+
+    for &IdRange { start: an, end: bn } in ranges {
+        let start_at = first_digits(an, count_base10_digits(an) / 2).max(1);
         for n in start_at.. {
             let repeat_twice = RepeatPatternIter::new(n).nth(1).unwrap();
 
@@ -35,10 +66,17 @@ fn run_on_input(input: &str) -> (usize, usize) {
                 pt1 += repeat_twice;
             }
         }
+    }
+
+    pt1
+}
 
-        // Part 2
+fn part2(ranges: &[IdRange]) -> usize {
+    let mut pt2 = 0;
+
+    for &IdRange { start: an, end: bn } in ranges {
         let mut already_found = HashSet::new();
-        let upper_bound = b.split_at(b.len().div_ceil(2)).0.parse::<usize>().unwrap() + 1;
+        let upper_bound = first_digits(bn, count_base10_digits(bn).div_ceil(2)) + 1;
         for n in 1..=upper_bound {
             for pattern in RepeatPatternIter::new(n).skip(1) {
                 if pattern > bn {
@@ -53,7 +91,12 @@ fn run_on_input(input: &str) -> (usize, usize) {
         }
     }
 
-    (pt1, pt2)
+    pt2
+}
+
+/// The number made from the first `count` base 10 digits of `n`, or 0 if `count` is 0
+fn first_digits(n: usize, count: usize) -> usize {
+    n / 10usize.pow((count_base10_digits(n) - count) as u32)
 }
 
 #[derive(Debug)]
@@ -96,6 +139,58 @@ fn count_base10_digits(n: usize) -> usize {
     count
 }
 
+/// Check that the input is a single line of comma-separated ranges like `11-22`, each starting no
+/// later than it ends
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut lines = validate::lines(input);
+    let Some((_, line)) = lines.next() else {
+        return vec![Violation::line(1, "input is empty")];
+    };
+
+    let mut violations = Vec::new();
+    for range in line.split(',') {
+        let Some((start, end)) = range.split_once('-') else {
+            violations.push(Violation::at(
+                1,
+                validate::column(line, range),
+                format!("expected a range like 11-22, found {range:?}"),
+            ));
+            continue;
+        };
+        let start = validate::number::<usize>(&mut violations, 1, line, start, "range start");
+        let end = validate::number::<usize>(&mut violations, 1, line, end, "range end");
+        if let (Some(start), Some(end)) = (start, end)
+            && start > end
+        {
+            violations.push(Violation::at(
+                1,
+                validate::column(line, range),
+                format!("range {range} ends before it starts"),
+            ));
+        }
+    }
+    for (n, _) in lines {
+        violations.push(Violation::line(
+            n,
+            "all the ranges should be on the first line",
+        ));
+    }
+    violations
+}
+
+/// `size` random ID ranges of up to ten digits
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let ranges: Vec<String> = (0..size)
+        .map(|_| {
+            let digits = rng.range(1..=10) as u32;
+            let start = rng.range(10usize.pow(digits - 1)..=10usize.pow(digits) - 1);
+            let end = start + rng.range(0..=start / 10);
+            format!("{start}-{end}")
+        })
+        .collect();
+    ranges.join(",") + "\n"
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,9 +199,9 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let (pt1, pt2) = run_on_input(EXAMPLE_INPUT);
-        assert_eq!(1227775554, pt1);
-        assert_eq!(4174379265, pt2);
+        let ranges = Day02.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(1227775554, part1(&ranges));
+        assert_eq!(4174379265, part2(&ranges));
     }
 
     #[test]
@@ -132,4 +227,21 @@ mod tests {
         assert_eq!(Some(321321321), rp.next());
         assert_eq!(Some(321321321321), rp.next());
     }
+
+    #[test]
+    fn test_validate() {
+        let violations: Vec<String> = validate("11-22,95-9a,30,50-40\n1-2\n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 1, column 11: unexpected 'a' in range end \"9a\"",
+                "line 1, column 13: expected a range like 11-22, found \"30\"",
+                "line 1, column 16: range 50-40 ends before it starts",
+                "line 2: all the ranges should be on the first line",
+            ],
+            violations
+        );
+    }
 }
@@ -1,30 +1,56 @@
-use crate::Aoc;
-
-const INPUT: &str = include_str!("../inputs/03.in");
+use crate::{
+    Answer, Solution,
+    error::AocError,
+    generate::Rng,
+    validate::{self, Violation},
+};
 
 pub struct Day03;
 
-impl Aoc for Day03 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+impl Solution for Day03 {
+    /// Each battery bank as ASCII digits
+    type Parsed = Vec<Vec<u8>>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        input
+            .trim()
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                if !line.bytes().all(|c| c.is_ascii_digit()) {
+                    return Err(AocError::parse(
+                        i + 1,
+                        line,
+                        "battery bank must be all digits",
+                    ));
+                }
+                if line.len() < 12 {
+                    return Err(AocError::parse(
+                        i + 1,
+                        line,
+                        "battery bank must have at least 12 batteries",
+                    ));
+                }
+                Ok(line.as_bytes().to_vec())
+            })
+            .collect()
     }
-}
 
-fn run_on_input(input: &str) -> (u64, u64) {
-    let pt1 = input
-        .trim()
-        .lines()
-        .map(|line| make_highest_number(line.as_bytes(), 2))
-        .sum();
+    fn part1(&self, banks: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(banks).into())
+    }
 
-    let pt2 = input
-        .trim()
-        .lines()
-        .map(|line| make_highest_number(line.as_bytes(), 12))
-        .sum();
+    fn part2(&self, banks: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part2(banks).into())
+    }
+}
+
+fn part1(banks: &[Vec<u8>]) -> u64 {
+    banks.iter().map(|bank| make_highest_number(bank, 2)).sum()
+}
 
-    (pt1, pt2)
+fn part2(banks: &[Vec<u8>]) -> u64 {
+    banks.iter().map(|bank| make_highest_number(bank, 12)).sum()
 }
 
 fn make_highest_number(line: &[u8], ndigits: usize) -> u64 {
@@ -56,6 +82,42 @@ fn first_maximum<T: Iterator<Item = u8>>(iter: T) -> (u8, usize) {
     (highest - b'0', position)
 }
 
+/// Check that every line is a bank of at least 12 batteries, each a single digit
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if input.trim().is_empty() {
+        violations.push(Violation::line(1, "input is empty"));
+    }
+    for (n, line) in validate::lines(input) {
+        if let Some((i, c)) = line.chars().enumerate().find(|(_, c)| !c.is_ascii_digit()) {
+            violations.push(Violation::at(
+                n,
+                i + 1,
+                format!("unexpected {c:?}, batteries are digits"),
+            ));
+        } else if line.len() < 12 {
+            violations.push(Violation::line(
+                n,
+                format!("bank has {} batteries, at least 12 are needed", line.len()),
+            ));
+        }
+    }
+    violations
+}
+
+/// `size` battery banks of 100 batteries each, as in the puzzle input
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let mut bank: String = (0..100)
+                .map(|_| char::from(b'0' + rng.range(1..=9) as u8))
+                .collect();
+            bank.push('\n');
+            bank
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,8 +126,23 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let (pt1, pt2) = run_on_input(EXAMPLE_INPUT);
-        assert_eq!(357, pt1);
-        assert_eq!(3121910778619, pt2);
+        let banks = Day03.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(357, part1(&banks));
+        assert_eq!(3121910778619, part2(&banks));
+    }
+
+    #[test]
+    fn test_validate() {
+        let violations: Vec<String> = validate("987654321111111\n12345\n1234567890x1234\n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 2: bank has 5 batteries, at least 12 are needed",
+                "line 3, column 11: unexpected 'x', batteries are digits",
+            ],
+            violations
+        );
     }
 }
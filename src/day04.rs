@@ -1,22 +1,36 @@
-use std::{convert::Infallible, str::FromStr};
+use std::str::FromStr;
 
-use crate::Aoc;
-
-const INPUT: &str = include_str!("../inputs/04.in");
+use crate::{
+    Answer, Solution,
+    error::AocError,
+    generate::Rng,
+    validate::{self, Violation},
+};
 
 pub struct Day04;
 
-impl Aoc for Day04 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+impl Solution for Day04 {
+    type Parsed = Grid;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        input.parse()
+    }
+
+    fn part1(&self, grid: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(grid).into())
+    }
+
+    fn part2(&self, grid: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part2(grid).into())
     }
 }
 
-fn run_on_input(input: &str) -> (usize, usize) {
-    let mut grid: Grid = input.parse().unwrap();
-    let pt1 = grid.removable_rolls().count();
+fn part1(grid: &Grid) -> usize {
+    grid.removable_rolls().count()
+}
 
+fn part2(grid: &Grid) -> usize {
+    let mut grid = grid.clone();
     let mut pt2 = 0;
 
     loop {
@@ -31,11 +45,11 @@ fn run_on_input(input: &str) -> (usize, usize) {
         }
     }
 
-    (pt1, pt2)
+    pt2
 }
 
-#[derive(Debug)]
-struct Grid {
+#[derive(Debug, Clone)]
+pub struct Grid {
     points: Vec<Vec<u8>>,
 }
 
@@ -81,10 +95,24 @@ impl Grid {
 }
 
 impl FromStr for Grid {
-    type Err = Infallible;
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let points: Vec<Vec<u8>> = s.trim().lines().map(|l| l.as_bytes().into()).collect();
+
+        if let Some((i, line)) = s
+            .trim()
+            .lines()
+            .enumerate()
+            .find(|(_, line)| !line.bytes().all(|c| c == b'@' || c == b'.'))
+        {
+            return Err(AocError::parse(
+                i + 1,
+                line,
+                "grid must only contain '@' and '.'",
+            ));
+        }
+
         Ok(Self { points })
     }
 }
@@ -108,6 +136,46 @@ fn neighboring_points_iter(x: usize, y: usize) -> impl Iterator<Item = (usize, u
     })
 }
 
+/// Check that the grid only has '@' and '.' in it, and that every row is the same width
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    if input.trim().is_empty() {
+        violations.push(Violation::line(1, "input is empty"));
+    }
+    let width = input.lines().next().map(str::len).unwrap_or_default();
+    for (n, line) in validate::lines(input) {
+        for (i, c) in line.chars().enumerate() {
+            if c != '@' && c != '.' {
+                violations.push(Violation::at(
+                    n,
+                    i + 1,
+                    format!("unexpected {c:?}, expected '@' or '.'"),
+                ));
+            }
+        }
+        if line.len() != width {
+            violations.push(Violation::line(
+                n,
+                format!("row is {} wide, but the first row is {width}", line.len()),
+            ));
+        }
+    }
+    violations
+}
+
+/// A square grid `size` tiles wide, with a roll of paper on about two thirds of the tiles
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let mut row: String = (0..size)
+                .map(|_| if rng.chance(2, 3) { '@' } else { '.' })
+                .collect();
+            row.push('\n');
+            row
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -118,9 +186,9 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let (pt1, pt2) = run_on_input(EXAMPLE_INPUT);
-        assert_eq!(13, pt1);
-        assert_eq!(43, pt2);
+        let grid = Day04.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(13, part1(&grid));
+        assert_eq!(43, part2(&grid));
     }
 
     #[test]
@@ -142,4 +210,20 @@ mod tests {
         ]);
         assert_eq!(neighbors, expected);
     }
+
+    #[test]
+    fn test_validate() {
+        let violations: Vec<String> = validate("..@@.\n@x@.#\n@@@\n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 2, column 2: unexpected 'x', expected '@' or '.'",
+                "line 2, column 5: unexpected '#', expected '@' or '.'",
+                "line 3: row is 3 wide, but the first row is 5",
+            ],
+            violations
+        );
+    }
 }
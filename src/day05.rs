@@ -1,34 +1,52 @@
 use std::{ops::RangeInclusive, str::FromStr};
 
-use crate::Aoc;
-
-const INPUT: &str = include_str!("../inputs/05.in");
+use crate::{
+    Answer, Solution,
+    error::{AocError, parse_num},
+    generate::Rng,
+    validate::{self, Violation},
+};
 
 pub struct Day05;
 
-impl Aoc for Day05 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+impl Solution for Day05 {
+    type Parsed = Inventory;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        input.parse()
+    }
+
+    fn part1(&self, inventory: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(inventory).into())
     }
-}
 
-fn run_on_input(input: &str) -> (usize, usize) {
-    let Inventory {
-        mut fresh_ranges,
-        available_ingredients,
-    } = input.parse().unwrap();
+    fn part2(&self, inventory: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part2(inventory)?.into())
+    }
+}
 
-    let pt1 = available_ingredients
+fn part1(inventory: &Inventory) -> usize {
+    inventory
+        .available_ingredients
         .iter()
-        .filter(|id| fresh_ranges.iter().any(|range| range.contains(id)))
-        .count();
+        .filter(|id| {
+            inventory
+                .fresh_ranges
+                .iter()
+                .any(|range| range.contains(id))
+        })
+        .count()
+}
 
+fn part2(inventory: &Inventory) -> Result<usize, AocError> {
+    let mut fresh_ranges = inventory.fresh_ranges.clone();
     fresh_ranges.sort_unstable_by_key(|range| *range.start());
     let mut fresh_ranges = fresh_ranges.into_iter();
 
     let mut merged_ranges = Vec::new();
-    let mut current_merged = fresh_ranges.next().unwrap();
+    let mut current_merged = fresh_ranges
+        .next()
+        .ok_or_else(|| AocError::unsolvable("there are no fresh ranges"))?;
 
     for next_range in fresh_ranges {
         match merge_ranges(current_merged, next_range) {
@@ -47,35 +65,51 @@ fn run_on_input(input: &str) -> (usize, usize) {
 
     merged_ranges.push(current_merged);
 
-    let pt2 = merged_ranges.into_iter().flatten().count();
-
-    (pt1, pt2)
+    Ok(merged_ranges.into_iter().flatten().count())
 }
 
 #[derive(Debug, Clone)]
-struct Inventory {
+pub struct Inventory {
     fresh_ranges: Vec<RangeInclusive<u64>>,
     available_ingredients: Vec<u64>,
 }
 
 impl FromStr for Inventory {
-    type Err = ();
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (ranges, ids) = s.trim().split_once("\n\n").unwrap();
+        let (ranges, ids) = s.trim_end().split_once("\n\n").ok_or_else(|| {
+            AocError::parse(
+                1,
+                s.lines().next().unwrap_or_default(),
+                "expected a blank line between the fresh ranges and the ingredient IDs",
+            )
+        })?;
 
         let fresh_ranges = ranges
-            .trim()
             .lines()
-            .map(|line| {
-                let (start, end) = line.split_once('-').unwrap();
-                let start = start.parse().unwrap();
-                let end = end.parse().unwrap();
-                start..=end
+            .enumerate()
+            .map(|(i, line)| {
+                let (start, end) = line
+                    .split_once('-')
+                    .ok_or_else(|| AocError::parse(i + 1, line, "expected a range like 3-5"))?;
+                let start =
+                    parse_num(start, "range start").map_err(|e| AocError::parse(i + 1, line, e))?;
+                let end =
+                    parse_num(end, "range end").map_err(|e| AocError::parse(i + 1, line, e))?;
+                Ok(start..=end)
             })
-            .collect();
+            .collect::<Result<_, AocError>>()?;
 
-        let available_ingredients = ids.trim().lines().map(|n| n.parse().unwrap()).collect();
+        // Skip the ranges and the blank line separating them from the IDs
+        let ids_offset = ranges.lines().count() + 1;
+        let available_ingredients = ids
+            .lines()
+            .enumerate()
+            .map(|(i, n)| {
+                parse_num(n, "ingredient ID").map_err(|e| AocError::parse(ids_offset + i + 1, n, e))
+            })
+            .collect::<Result<_, AocError>>()?;
 
         Ok(Self {
             fresh_ranges,
@@ -101,6 +135,64 @@ fn merge_ranges(
     }
 }
 
+/// Check that the input is fresh ranges like `3-5`, each starting no later than it ends, then a
+/// blank line, then one ingredient ID per line
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut range_count = 0;
+    let mut in_ids = false;
+    for (n, line) in validate::lines(input) {
+        if line.is_empty() {
+            if in_ids {
+                violations.push(Violation::line(n, "unexpected blank line among the IDs"));
+            }
+            in_ids = true;
+        } else if in_ids {
+            validate::number::<u64>(&mut violations, n, line, line, "ingredient ID");
+        } else {
+            range_count += 1;
+            let Some((start, end)) = line.split_once('-') else {
+                violations.push(Violation::line(n, "expected a range like 3-5"));
+                continue;
+            };
+            let start = validate::number::<u64>(&mut violations, n, line, start, "range start");
+            let end = validate::number::<u64>(&mut violations, n, line, end, "range end");
+            if let (Some(start), Some(end)) = (start, end)
+                && start > end
+            {
+                violations.push(Violation::line(n, "range ends before it starts"));
+            }
+        }
+    }
+
+    if !in_ids {
+        violations.push(Violation::line(
+            1,
+            "expected a blank line between the fresh ranges and the ingredient IDs",
+        ));
+    } else if range_count == 0 {
+        violations.push(Violation::line(1, "there are no fresh ranges"));
+    }
+    violations
+}
+
+/// `size` fresh ID ranges, many of which overlap, followed by `size` ingredient IDs
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const MAX_RANGE_LENGTH: usize = 20_000;
+    let max_id = size * 10_000;
+
+    let mut input = String::new();
+    for _ in 0..size {
+        let start = rng.range(1..=max_id);
+        input += &format!("{start}-{}\n", start + rng.range(0..=MAX_RANGE_LENGTH));
+    }
+    input.push('\n');
+    for _ in 0..size {
+        input += &format!("{}\n", rng.range(1..=max_id + MAX_RANGE_LENGTH));
+    }
+    input
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,9 +201,9 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let (pt1, pt2) = run_on_input(EXAMPLE_INPUT);
-        assert_eq!(3, pt1);
-        assert_eq!(14, pt2);
+        let inventory = Day05.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(3, part1(&inventory));
+        assert_eq!(14, part2(&inventory).unwrap());
     }
 
     #[test]
@@ -120,4 +212,28 @@ mod tests {
         assert_eq!(Ok(3..=11), merge_ranges(3..=6, 6..=11));
         assert_eq!(Ok(5..=20), merge_ranges(5..=20, 8..=10));
     }
+
+    #[test]
+    fn test_validate() {
+        let violations: Vec<String> = validate("3-5\n20-10\n12\n\n1\n-5\n\n8\n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 2: range ends before it starts",
+                "line 3: expected a range like 3-5",
+                "line 6, column 1: ingredient ID -5 can't be negative",
+                "line 7: unexpected blank line among the IDs",
+            ],
+            violations
+        );
+        assert_eq!(
+            vec![Violation::line(
+                1,
+                "expected a blank line between the fresh ranges and the ingredient IDs"
+            )],
+            validate("3-5\n")
+        );
+    }
 }
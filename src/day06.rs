@@ -1,41 +1,60 @@
 use std::str::FromStr;
 
-use crate::Aoc;
-
-const INPUT: &str = include_str!("../inputs/06.in");
+use crate::{
+    Answer, Solution,
+    error::{AocError, parse_num},
+    generate::Rng,
+    validate::{self, Violation},
+};
 
 pub struct Day06;
 
-impl Aoc for Day06 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+impl Solution for Day06 {
+    type Parsed = Columns;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        input.parse()
     }
-}
 
-fn run_on_input(input: &str) -> (u64, u64) {
-    let columns: Columns = input.parse().unwrap();
+    fn part1(&self, columns: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(columns)?.into())
+    }
+
+    fn part2(&self, columns: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part2(columns)?.into())
+    }
+}
 
-    let pt1 = columns
+fn part1(columns: &Columns) -> Result<u64, AocError> {
+    columns
         .0
         .iter()
         .map(|column| {
-            let mut column_iter = column.iter();
-            let operation: Operation = column_iter.next().unwrap().parse().unwrap();
-            column_iter.fold(0, |accum, item| {
-                let n: u64 = item.trim().parse().unwrap();
-                match operation {
-                    Operation::Add => accum + n,
-                    Operation::Multiply => match accum {
-                        0 => n,
-                        _ => accum * n,
-                    },
-                }
-            })
+            let operation = column_operation(column)?;
+            // Each column holds one entry per line, stored bottom to top, so count back from the
+            // last line for errors
+            let line_count = column.len();
+            column
+                .iter()
+                .skip(1)
+                .enumerate()
+                .try_fold(0, |accum, (i, item)| {
+                    let n: u64 = parse_num(item, "number")
+                        .map_err(|e| AocError::parse(line_count - i - 1, item, e))?;
+                    Ok(match operation {
+                        Operation::Add => accum + n,
+                        Operation::Multiply => match accum {
+                            0 => n,
+                            _ => accum * n,
+                        },
+                    })
+                })
         })
-        .sum();
+        .sum()
+}
 
-    let pt2 = columns
+fn part2(columns: &Columns) -> Result<u64, AocError> {
+    columns
         .0
         .iter()
         .map(|column| {
@@ -43,8 +62,8 @@ fn run_on_input(input: &str) -> (u64, u64) {
             let ops_str = column_iter.next().unwrap();
             let column_iter = column_iter; // immutable
             let l = ops_str.len(); // all strings are the same length
-            let operation: Operation = ops_str.parse().unwrap();
-            (0..l)
+            let operation = column_operation(column)?;
+            let result = (0..l)
                 .map(|i| {
                     column_iter.clone().rev().fold(None, |accum, item| {
                         let ch = item.as_bytes()[i];
@@ -71,11 +90,19 @@ fn run_on_input(input: &str) -> (u64, u64) {
                             _ => accum * n,
                         },
                     }
-                })
+                });
+            Ok(result)
         })
-        .sum();
+        .sum()
+}
 
-    (pt1, pt2)
+/// Parse the operation from the bottom line of a column
+fn column_operation(column: &[String]) -> Result<Operation, AocError> {
+    let line_count = column.len();
+    let ops_str = column.first().map(String::as_str).unwrap_or_default();
+    ops_str
+        .parse()
+        .map_err(|_| AocError::parse(line_count, ops_str, "expected '+' or '*'"))
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -97,14 +124,32 @@ impl FromStr for Operation {
 }
 
 #[derive(Debug, Clone)]
-struct Columns(Vec<Vec<String>>);
+pub struct Columns(Vec<Vec<String>>);
 
 impl FromStr for Columns {
-    type Err = ();
+    type Err = AocError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let lines = s.lines().rev();
 
+        if s.trim().is_empty() {
+            return Err(AocError::parse(1, s, "worksheet is empty"));
+        }
+
+        // Columns are sliced out of every line at the same offsets, so the lines must line up
+        let width = s.lines().next().map(str::len).unwrap_or_default();
+        if let Some((i, line)) = s
+            .lines()
+            .enumerate()
+            .find(|(_, line)| line.len() != width || !line.is_ascii())
+        {
+            return Err(AocError::parse(
+                i + 1,
+                line,
+                format!("all lines must be {width} ASCII characters long"),
+            ));
+        }
+
         let mut start = 0;
         let mut result = Vec::new();
         let mut found_end = false;
@@ -136,6 +181,116 @@ impl FromStr for Columns {
     }
 }
 
+/// Check that the worksheet is rows of numbers above a row of operators, all lined up so that each
+/// problem is separated from the next by a column of spaces, and has one operator and a number on
+/// every row
+pub fn validate(input: &str) -> Vec<Violation> {
+    // Trailing spaces matter here, so the lines aren't trimmed
+    let lines: Vec<&str> = input.lines().collect();
+    let Some((operators, numbers)) = lines.split_last() else {
+        return vec![Violation::line(1, "input is empty")];
+    };
+    let operators_line = lines.len();
+
+    let mut violations = Vec::new();
+    let width = lines[0].len();
+    for (i, line) in lines.iter().enumerate() {
+        let allowed: &[char] = if i + 1 == operators_line {
+            &['+', '*', ' ']
+        } else {
+            &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ' ']
+        };
+        if let Some((column, c)) = line.chars().enumerate().find(|(_, c)| !allowed.contains(c)) {
+            violations.push(Violation::at(
+                i + 1,
+                column + 1,
+                format!("unexpected {c:?}"),
+            ));
+        } else if line.len() != width {
+            violations.push(Violation::line(
+                i + 1,
+                format!(
+                    "line is {} characters long, the first is {width}",
+                    line.len()
+                ),
+            ));
+        }
+    }
+    if numbers.is_empty() {
+        violations.push(Violation::line(
+            1,
+            "expected rows of numbers above the operators",
+        ));
+    }
+    // Problems can't be found if the columns don't line up
+    if !violations.is_empty() {
+        return violations;
+    }
+
+    let is_gap = |i: usize| lines.iter().all(|line| line.as_bytes()[i] == b' ');
+    let mut start = 0;
+    for end in (0..=width).filter(|&i| i == width || is_gap(i)) {
+        if end > start {
+            let operator_count = operators[start..end].chars().filter(|&c| c != ' ').count();
+            if operator_count != 1 {
+                violations.push(Violation::at(
+                    operators_line,
+                    start + 1,
+                    format!("problem has {operator_count} operators, expected 1"),
+                ));
+            }
+            for (i, line) in numbers.iter().enumerate() {
+                let number = line[start..end].trim();
+                if number.is_empty() {
+                    violations.push(Violation::at(
+                        i + 1,
+                        start + 1,
+                        "problem has no number here",
+                    ));
+                } else if let Some(gap) = number.find(' ') {
+                    violations.push(Violation::at(
+                        i + 1,
+                        validate::column(line, number) + gap,
+                        "number has a gap in it",
+                    ));
+                }
+            }
+        }
+        start = end + 1;
+    }
+    violations
+}
+
+/// A worksheet of `size` problems, each with three numbers of up to four digits. The numbers in
+/// each problem are lined up on either the left or the right, as in the puzzle input.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const NUMBER_ROWS: usize = 3;
+    let mut lines = vec![String::new(); NUMBER_ROWS + 1];
+    for i in 0..size {
+        let numbers: Vec<String> = (0..NUMBER_ROWS)
+            .map(|_| {
+                let digits = rng.range(1..=4) as u32;
+                rng.range(10usize.pow(digits - 1)..=10usize.pow(digits) - 1)
+                    .to_string()
+            })
+            .collect();
+        let width = numbers.iter().map(String::len).max().unwrap_or_default();
+        let right_aligned = rng.chance(1, 2);
+        let separator = if i == 0 { "" } else { " " };
+        for (line, number) in lines.iter_mut().zip(&numbers) {
+            if right_aligned {
+                *line += &format!("{separator}{number:>width$}");
+            } else {
+                *line += &format!("{separator}{number:<width$}");
+            }
+        }
+
+        let operation = if rng.chance(1, 2) { '+' } else { '*' };
+        lines[NUMBER_ROWS] += &format!("{separator}{operation:<width$}");
+    }
+    lines.join("\n") + "\n"
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,8 +299,37 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let (pt1, pt2) = run_on_input(EXAMPLE_INPUT);
-        assert_eq!(4277556, pt1);
-        assert_eq!(3263827, pt2);
+        let columns = Day06.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(4277556, part1(&columns).unwrap());
+        assert_eq!(3263827, part2(&columns).unwrap());
+    }
+
+    #[test]
+    fn test_validate() {
+        let violations: Vec<String> = validate("12 3 4\n4    5\n1x 6 7\n*  + \n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 3, column 2: unexpected 'x'",
+                "line 4: line is 5 characters long, the first is 6",
+            ],
+            violations
+        );
+
+        let violations: Vec<String> = validate("1 2  3\n45 6 7\n     8\n*  + +\n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 4, column 1: problem has 2 operators, expected 1",
+                "line 1, column 2: number has a gap in it",
+                "line 2, column 3: number has a gap in it",
+                "line 3, column 1: problem has no number here",
+            ],
+            violations
+        );
     }
 }
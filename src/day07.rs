@@ -1,31 +1,63 @@
 use std::collections::HashMap;
 
-use crate::Aoc;
-
-const INPUT: &str = include_str!("../inputs/07.in");
+use crate::{
+    Answer, Solution,
+    error::AocError,
+    generate::Rng,
+    validate::{self, Violation},
+};
 
 pub struct Day07;
 
-impl Aoc for Day07 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+impl Solution for Day07 {
+    type Parsed = Manifold;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        let mut lines = input.lines();
+
+        let first_line = lines.next().unwrap_or_default();
+        let start = first_line.find('S').ok_or_else(|| {
+            AocError::parse(1, first_line, "first line must contain the start 'S'")
+        })?;
+
+        Ok(Manifold {
+            start,
+            rows: lines.map(|line| line.as_bytes().to_vec()).collect(),
+        })
+    }
+
+    fn part1(&self, manifold: &Self::Parsed) -> Result<Answer, AocError> {
+        let (splits, _) = simulate(manifold);
+        Ok(splits.into())
+    }
+
+    fn part2(&self, manifold: &Self::Parsed) -> Result<Answer, AocError> {
+        let (_, timelines) = simulate(manifold);
+        Ok(timelines.into())
     }
 }
 
-fn run_on_input(input: &str) -> (usize, usize) {
-    let mut lines = input.lines();
+#[derive(Debug, Clone)]
+pub struct Manifold {
+    /// Position of the start 'S' on the first line
+    start: usize,
+    /// Every line after the first
+    rows: Vec<Vec<u8>>,
+}
 
+/// Send the beam down through the manifold, returning the number of times it is split and the
+/// number of particles (timelines) that come out of the bottom.
+fn simulate(manifold: &Manifold) -> (usize, usize) {
     // HashMap of (position, particle_count)
     let mut beam_positions = HashMap::new();
-    beam_positions.insert(lines.next().unwrap().find('S').unwrap(), 1);
+    beam_positions.insert(manifold.start, 1);
 
     let mut pt1 = 0;
 
-    for line in lines {
+    for line in manifold.rows.iter() {
         let mut new_beam_positions = HashMap::new();
         for (position, count) in beam_positions.iter() {
-            match line.as_bytes().get(*position) {
+            match line.get(*position) {
                 Some(b'^') => {
                     pt1 += 1;
                     *new_beam_positions.entry(*position + 1).or_insert(0) += count;
@@ -48,6 +80,59 @@ fn run_on_input(input: &str) -> (usize, usize) {
     (pt1, pt2)
 }
 
+/// Check that the first row has the start 'S' and is otherwise empty, that the other rows only have
+/// empty space and splitters, and that every row is the same width
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let width = input.lines().next().map(str::len).unwrap_or_default();
+    for (n, line) in validate::lines(input) {
+        let allowed: &[char] = if n == 1 { &['S', '.'] } else { &['^', '.'] };
+        for (i, c) in line.chars().enumerate() {
+            if !allowed.contains(&c) {
+                violations.push(Violation::at(n, i + 1, format!("unexpected {c:?}")));
+            }
+        }
+        if line.len() != width {
+            violations.push(Violation::line(
+                n,
+                format!("row is {} wide, but the first row is {width}", line.len()),
+            ));
+        }
+    }
+
+    let first_line = input.lines().next().unwrap_or_default();
+    match first_line.matches('S').count() {
+        1 => {}
+        0 => violations.push(Violation::line(1, "first row must contain the start 'S'")),
+        n => violations.push(Violation::line(1, format!("found {n} starts, expected 1"))),
+    }
+    violations
+}
+
+/// A manifold `size` tiles wide and high, starting in the middle of the top row, with splitters on
+/// every other row as in the puzzle input. The splitters are sparser in larger manifolds, so that
+/// the number of timelines stays well within a `usize`.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // Each row of splitters multiplies the number of timelines by roughly (1 + density), so aim
+    // for about 2^40 timelines in total
+    let splitter_rows = (size / 2).max(1) as f64;
+    let per_thousand = ((2f64.powf(40.0 / splitter_rows) - 1.0) * 1000.0).clamp(1.0, 500.0) as u64;
+
+    (0..size)
+        .map(|y| {
+            let mut row: String = (0..size)
+                .map(|x| match y {
+                    0 if x == size / 2 => 'S',
+                    _ if y % 2 == 0 && y > 0 && rng.chance(per_thousand, 1000) => '^',
+                    _ => '.',
+                })
+                .collect();
+            row.push('\n');
+            row
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,8 +141,24 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let (pt1, pt2) = run_on_input(EXAMPLE_INPUT);
+        let (pt1, pt2) = simulate(&Day07.parse(EXAMPLE_INPUT).unwrap());
         assert_eq!(21, pt1);
         assert_eq!(40, pt2);
     }
+
+    #[test]
+    fn test_validate() {
+        let violations: Vec<String> = validate("..S.S\n.....\n.^#.\n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 3, column 3: unexpected '#'",
+                "line 3: row is 4 wide, but the first row is 5",
+                "line 1: found 2 starts, expected 1",
+            ],
+            violations
+        );
+    }
 }
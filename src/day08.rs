@@ -1,88 +1,194 @@
 use std::{
+    cmp::Ordering,
     collections::{HashMap, HashSet},
     hash::Hash,
     str::FromStr,
 };
 
 use itertools::Itertools;
+use rayon::prelude::*;
+
+use crate::{
+    Answer, Solution,
+    answers::Params,
+    error::{AocError, parse_lines, parse_num},
+    generate::Rng,
+    parallel, trace,
+    validate::{self, Violation},
+};
 
-use crate::Aoc;
-
-const INPUT: &str = include_str!("../inputs/08.in");
+/// Number of connections to make before calculating the part 1 answer
+const MERGE_COUNT: usize = 1000;
 
 pub struct Day08;
 
-impl Aoc for Day08 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT, 1000);
-        (pt1.to_string(), pt2.to_string())
+impl Solution for Day08 {
+    type Parsed = Playground;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        let nodes: Vec<Point3D> = parse_lines(input)?;
+        let connections = if parallel::enabled() {
+            connections_parallel(&nodes)
+        } else {
+            connections(&nodes)
+        };
+
+        Ok(Playground {
+            nodes,
+            connections,
+            merge_count: MERGE_COUNT,
+        })
+    }
+
+    /// The examples make fewer connections for part 1, which is given as `merge_count`
+    fn parse_with_params(&self, input: &str, params: &Params) -> Result<Self::Parsed, AocError> {
+        params.check_known(&["merge_count"])?;
+        let mut playground = self.parse(input)?;
+        if let Some(merge_count) = params.get("merge_count")? {
+            playground.merge_count = merge_count;
+        }
+        Ok(playground)
+    }
+
+    fn part1(&self, playground: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(playground, playground.merge_count)?.into())
+    }
+
+    fn part2(&self, playground: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part2(playground)?.into())
     }
 }
 
-fn run_on_input(input: &str, merge_count: usize) -> (usize, i64) {
-    let nodes: Vec<Point3D> = input
-        .trim()
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect();
+/// The junction boxes, along with every possible connection between them. Both parts make
+/// connections in the same order, so the (expensive) sorting is shared between them.
+#[derive(Debug, Clone)]
+pub struct Playground {
+    nodes: Vec<Point3D>,
+    /// Pairs of indices into `nodes`, sorted from shortest to longest distance
+    connections: Vec<(usize, usize)>,
+    /// Number of connections to make for part 1
+    merge_count: usize,
+}
 
-    let mut connections: Vec<(f64, &Point3D, &Point3D)> = nodes
+/// Every pair of nodes, sorted from shortest to longest distance
+fn connections(nodes: &[Point3D]) -> Vec<(usize, usize)> {
+    let distances = trace::span("distances");
+    let mut connections: Vec<(f64, usize, usize)> = nodes
         .iter()
+        .enumerate()
         .tuple_combinations()
-        .map(|(a, b)| {
+        .map(|((i, a), (j, b))| {
             let distance = a.euclidean_distance(b);
-            (distance, a, b)
+            (distance, i, j)
         })
         .collect();
+    drop(distances);
+
+    // Sort smallest to largest
+    let _sort = trace::span("sort");
+    connections.sort_unstable_by(compare_connections);
+    connections.into_iter().map(|(_, a, b)| (a, b)).collect()
+}
 
-    // Sort largest to smallest
-    connections.sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+/// Same as [`connections`], but calculates the distances and sorts them in parallel
+fn connections_parallel(nodes: &[Point3D]) -> Vec<(usize, usize)> {
+    let distances = trace::span("distances");
+    let mut connections: Vec<(f64, usize, usize)> = (0..nodes.len())
+        .into_par_iter()
+        .flat_map_iter(|i| {
+            (i + 1..nodes.len()).map(move |j| (nodes[i].euclidean_distance(&nodes[j]), i, j))
+        })
+        .collect();
+    drop(distances);
+
+    let _sort = trace::span("sort");
+    connections.par_sort_unstable_by(compare_connections);
+    connections
+        .into_par_iter()
+        .map(|(_, a, b)| (a, b))
+        .collect()
+}
+
+/// Order connections by distance. Ties are broken by the node indices, so that the order doesn't
+/// depend on how the sort was done.
+fn compare_connections(a: &(f64, usize, usize), b: &(f64, usize, usize)) -> Ordering {
+    a.0.total_cmp(&b.0).then((a.1, a.2).cmp(&(b.1, b.2)))
+}
 
+fn part1(playground: &Playground, merge_count: usize) -> Result<usize, AocError> {
+    let Playground {
+        nodes, connections, ..
+    } = playground;
+
+    if connections.len() < merge_count {
+        return Err(AocError::unsolvable(format!(
+            "need {merge_count} connections but there are only {} junction boxes",
+            nodes.len()
+        )));
+    }
+
+    let merges = trace::span("union-find");
     let mut ds = DisjointSet::new(nodes.clone());
 
     // Make the number of connections required for part 1
-    for _ in 0..merge_count {
-        let (_, a, b) = connections.pop().unwrap();
-        ds.merge(a, b);
+    for &(a, b) in connections.iter().take(merge_count) {
+        ds.merge(&nodes[a], &nodes[b]);
     }
+    drop(merges);
 
     // Get the sets of connected nodes at this point in time, and multiply the largest 3.
+    let _sets = trace::span("largest sets");
     let mut sets: Vec<_> = ds.as_sets().into_values().map(|set| set.len()).collect();
     sets.sort_unstable();
-    let pt1 = sets.iter().rev().take(3).product();
+    Ok(sets.iter().rev().take(3).product())
+}
+
+fn part2(playground: &Playground) -> Result<i64, AocError> {
+    let Playground {
+        nodes, connections, ..
+    } = playground;
 
-    // Do the rest of the connections until we have one giant set
-    let pt2 = loop {
-        let (_, a, b) = connections
-            .pop()
-            .expect("must reach pt2 answer before exhausting connections");
+    let _merges = trace::span("union-find");
+    let mut ds = DisjointSet::new(nodes.clone());
 
+    // Make connections until we have one giant set
+    for &(a, b) in connections.iter() {
+        let (a, b) = (&nodes[a], &nodes[b]);
         if matches!(ds.merge(a, b), Some(FullyMerged)) {
-            break a.x * b.x;
+            return Ok(a.x * b.x);
         }
-    };
+    }
 
-    (pt1, pt2)
+    Err(AocError::unsolvable(
+        "ran out of connections before all junction boxes were connected",
+    ))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Point3D {
+pub struct Point3D {
     x: i64,
     y: i64,
     z: i64,
 }
 
 impl FromStr for Point3D {
-    type Err = ();
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.trim();
-        let (x, yz) = s.split_once(',').unwrap();
-        let (y, z) = yz.split_once(',').unwrap();
+        let mut coordinates = s.split(',');
+        let (Some(x), Some(y), Some(z), None) = (
+            coordinates.next(),
+            coordinates.next(),
+            coordinates.next(),
+            coordinates.next(),
+        ) else {
+            return Err("expected three comma-separated coordinates".to_string());
+        };
         Ok(Point3D {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-            z: z.parse().unwrap(),
+            x: parse_num(x, "x coordinate")?,
+            y: parse_num(y, "y coordinate")?,
+            z: parse_num(z, "z coordinate")?,
         })
     }
 }
@@ -193,17 +299,73 @@ impl<T> Default for DisjointSetItem<T> {
     }
 }
 
+/// Check that every line is a junction box position like `162,817,812`, with no two boxes in the
+/// same place
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut seen = HashMap::new();
+    for (n, line) in validate::lines(input) {
+        let coordinates: Vec<&str> = line.split(',').collect();
+        if coordinates.len() != 3 {
+            violations.push(Violation::line(
+                n,
+                "expected three comma-separated coordinates",
+            ));
+            continue;
+        }
+        let [x, y, z] = [0, 1, 2].map(|i| {
+            validate::number::<i64>(&mut violations, n, line, coordinates[i], "coordinate")
+        });
+        if let (Some(x), Some(y), Some(z)) = (x, y, z)
+            && let Some(first) = seen.insert((x, y, z), n)
+        {
+            // The boxes are told apart by their positions
+            violations.push(Violation::line(
+                n,
+                format!("same position as the junction box on line {first}"),
+            ));
+        }
+    }
+    if seen.len() < 2 && violations.is_empty() {
+        violations.push(Violation::line(1, "need at least two junction boxes"));
+    }
+    violations
+}
+
+/// `size` junction boxes at random positions. Part 1 needs at least 46 of them, to have enough
+/// pairs for its 1000 connections.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let [x, y, z] = [(); 3].map(|_| rng.range(0..=99_999));
+            format!("{x},{y},{z}\n")
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{generate::Rng, testing::with_threads};
 
     const EXAMPLE_INPUT: &str = include_str!("../inputs/08.ex");
 
     #[test]
     fn test_example() {
-        let (pt1, pt2) = run_on_input(EXAMPLE_INPUT, 10);
-        assert_eq!(40, pt1);
-        assert_eq!(25272, pt2);
+        let playground = Day08.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(40, part1(&playground, 10).unwrap());
+        assert_eq!(25272, part2(&playground).unwrap());
+    }
+
+    /// A generated input, with enough pairs of junction boxes for the distances and the sort to
+    /// be split between threads
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let playground = Day08.parse(&generate(&mut Rng::new(8), 1000)).unwrap();
+        assert_eq!(
+            connections(&playground.nodes),
+            with_threads(|| connections_parallel(&playground.nodes))
+        );
     }
 
     #[test]
@@ -222,4 +384,24 @@ mod tests {
         assert_eq!(Some(FullyMerged), ds.merge(&'a', &'d'));
         assert_eq!(ds.as_sets().len(), 1);
     }
+
+    #[test]
+    fn test_validate() {
+        let violations: Vec<String> = validate("1,2,3\n4,5\n1,2,3\n7,x,9\n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 2: expected three comma-separated coordinates",
+                "line 3: same position as the junction box on line 1",
+                "line 4, column 3: unexpected 'x' in coordinate \"x\"",
+            ],
+            violations
+        );
+        assert_eq!(
+            vec![Violation::line(1, "need at least two junction boxes")],
+            validate("1,2,3\n")
+        );
+    }
 }
@@ -1,84 +1,180 @@
-use std::collections::BTreeMap;
-
-use crate::Aoc;
+use std::{cmp::Reverse, collections::BTreeMap, str::FromStr};
+
+use crate::{
+    Answer, Aoc, Solution,
+    error::{AocError, parse_lines, parse_num},
+    generate::Rng,
+    parallel, trace,
+    validate::{self, Violation},
+};
 use itertools::Itertools;
-
-const INPUT: &str = include_str!("../inputs/09.in");
+use rayon::prelude::*;
 
 pub struct Day09;
 
-impl Aoc for Day09 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+/// Checks every tile of every rectangle for part 2, rather than looking for intersections with the
+/// boundary. This is only practical when the tiles fit in memory, and is used to check [`Day09`].
+pub struct Day09Naive;
+
+pub const ALTERNATIVES: &[(&str, &(dyn Aoc + Send + Sync))] = &[("naive", &Day09Naive)];
+
+/// The most tiles the naive solver will fill in
+const NAIVE_TILE_LIMIT: usize = 10_000_000;
+
+impl Solution for Day09 {
+    type Parsed = BoundingPolygon;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        BoundingPolygon::new(parse_lines(input)?)
+    }
+
+    fn part1(&self, polygon: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(polygon)?.into())
+    }
+
+    fn part2(&self, polygon: &Self::Parsed) -> Result<Answer, AocError> {
+        if parallel::enabled() {
+            Ok(part2_parallel(polygon)?.into())
+        } else {
+            Ok(part2(polygon)?.into())
+        }
     }
 }
 
-fn run_on_input(input: &str) -> (i64, i64) {
-    let coordinates: Vec<Point> = input
-        .trim()
-        .lines()
-        .map(|line| {
-            let (x, y) = line.split_once(',').unwrap();
-            Point::new(x.parse().unwrap(), y.parse().unwrap())
-        })
-        .collect();
+impl Solution for Day09Naive {
+    type Parsed = BoundingPolygon;
 
-    let mut rectangles: Vec<Rectangle> = coordinates
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        Day09.parse(input)
+    }
+
+    fn part1(&self, polygon: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(polygon)?.into())
+    }
+
+    fn part2(&self, polygon: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part2_naive(polygon)?.into())
+    }
+}
+
+fn part1(polygon: &BoundingPolygon) -> Result<i64, AocError> {
+    polygon
+        .points
         .iter()
         .tuple_combinations()
-        .map(|(a, b)| Rectangle::new(a.clone(), b.clone()))
-        .collect();
+        .map(|(a, b)| Rectangle::new(a.clone(), b.clone()).area())
+        .max()
+        .ok_or_else(|| AocError::unsolvable("need at least two red tiles to make a rectangle"))
+}
 
+fn part2(polygon: &BoundingPolygon) -> Result<i64, AocError> {
+    let mut rectangles = rectangles(polygon);
+    let sort = trace::span("sort");
     rectangles.sort_unstable_by_key(|rect| rect.area());
-    let largest_area = rectangles.last().unwrap().area();
+    drop(sort);
+    let _containment = trace::span("containment");
 
-    let mut bounding_polygon = BoundingPolygon::new(coordinates.clone());
+    // Work on a copy so that the containment cache starts empty on every run
+    let mut bounding_polygon = polygon.clone();
 
     while let Some(rectangle) = rectangles.pop() {
-        // A rectangle is a valid answer for part 2 if it's entirely contained in the bounding
-        // polygon.  A rectangle is definitely in the bounding polygon if:
-        //   1. All corners of the rectangle are inside or on the boundary
-        //   2. None of the boundary lines intersect the lines of the rectangle
-        //
-        // It's also possible for a rectangle to be a valid answer if the bounding polygon intrudes
-        // on the rectangle, but the intrusion is only of width 2 (and therefore there are no grid
-        // points in the intrusion that are not part of the bounding line). The solution assumes
-        // that this never happens.
-        let rectangle_is_inside_boundary = rectangle.corners().iter().all(|corner| {
-            matches!(
-                bounding_polygon.contains(corner),
-                Bounding::Inside | Bounding::OnBoundary
-            )
-        });
-
-        let any_boundary_lines_intersect_rectangle =
-            // Weirdly, having the rectangle line segments as the outer iteration and the bounding
-            // polygon as the inner seems to perform significantly better here. I have no idea
-            // why...
-            rectangle.iter_line_segments().any(|rectangle_seg| {
-                bounding_polygon.iter_line_segments().any(|boundary_seg| {
-                    match rectangle_seg.intersects(&boundary_seg) {
-                        Intersection::Intersecting => true,
-                        Intersection::NotIntersecting => false,
-                        Intersection::PerpendicularTouching(_point) => {
-                            // For now, we're not going to consider this as an intersection. We may
-                            // need to revisit this, because there is a case where two line segments
-                            // both touch the rectangle from opposite sides, so there is actually
-                            // an intersection but this would not count it as one. But for now we're
-                            // not considering this edge case...
-                            false
-                        }
-                    }
-                })
-            });
-
-        if rectangle_is_inside_boundary && !any_boundary_lines_intersect_rectangle {
-            return (largest_area, rectangle.area());
+        if rectangle_fits(&rectangle, &mut bounding_polygon) {
+            return Ok(rectangle.area());
         }
     }
 
-    panic!("No rectangles match the search criteria...");
+    Err(no_rectangle_fits())
+}
+
+/// Same as [`part2`], but checks the rectangles in parallel. The largest rectangle that fits is
+/// still the answer, however the work is split.
+fn part2_parallel(polygon: &BoundingPolygon) -> Result<i64, AocError> {
+    let mut rectangles = rectangles(polygon);
+    let sort = trace::span("sort");
+    rectangles.par_sort_unstable_by_key(|rect| Reverse(rect.area()));
+    drop(sort);
+    let _containment = trace::span("containment");
+
+    rectangles
+        .par_iter()
+        // Each rayon job gets its own copy of the polygon, so that the containment cache isn't
+        // shared between threads
+        .map_init(
+            || polygon.clone(),
+            |bounding_polygon, rectangle| {
+                rectangle_fits(rectangle, bounding_polygon).then(|| rectangle.area())
+            },
+        )
+        .find_first(Option::is_some)
+        .flatten()
+        .ok_or_else(no_rectangle_fits)
+}
+
+fn part2_naive(polygon: &BoundingPolygon) -> Result<i64, AocError> {
+    let tiles = Tiles::new(&polygon.points)?;
+    rectangles(polygon)
+        .into_iter()
+        .filter(|rectangle| tiles.covers(rectangle))
+        .map(|rectangle| rectangle.area())
+        .max()
+        .ok_or_else(no_rectangle_fits)
+}
+
+/// Every rectangle with two red tiles as opposite corners
+fn rectangles(polygon: &BoundingPolygon) -> Vec<Rectangle> {
+    let _span = trace::span("rectangles");
+    polygon
+        .points
+        .iter()
+        .tuple_combinations()
+        .map(|(a, b)| Rectangle::new(a.clone(), b.clone()))
+        .collect()
+}
+
+fn no_rectangle_fits() -> AocError {
+    AocError::unsolvable("no rectangles lie entirely within the polygon")
+}
+
+/// Whether the rectangle is a valid answer for part 2, which is when it's entirely contained in
+/// the bounding polygon.
+fn rectangle_fits(rectangle: &Rectangle, bounding_polygon: &mut BoundingPolygon) -> bool {
+    // A rectangle is definitely in the bounding polygon if:
+    //   1. All corners of the rectangle are inside or on the boundary
+    //   2. None of the boundary lines intersect the lines of the rectangle
+    //
+    // It's also possible for a rectangle to be a valid answer if the bounding polygon intrudes
+    // on the rectangle, but the intrusion is only of width 2 (and therefore there are no grid
+    // points in the intrusion that are not part of the bounding line). The solution assumes
+    // that this never happens.
+    let rectangle_is_inside_boundary = rectangle.corners().iter().all(|corner| {
+        matches!(
+            bounding_polygon.contains(corner),
+            Bounding::Inside | Bounding::OnBoundary
+        )
+    });
+
+    let any_boundary_lines_intersect_rectangle =
+        // Weirdly, having the rectangle line segments as the outer iteration and the bounding
+        // polygon as the inner seems to perform significantly better here. I have no idea
+        // why...
+        rectangle.iter_line_segments().any(|rectangle_seg| {
+            bounding_polygon.iter_line_segments().any(|boundary_seg| {
+                match rectangle_seg.intersects(&boundary_seg) {
+                    Intersection::Intersecting => true,
+                    Intersection::NotIntersecting => false,
+                    Intersection::PerpendicularTouching(_point) => {
+                        // For now, we're not going to consider this as an intersection. We may
+                        // need to revisit this, because there is a case where two line segments
+                        // both touch the rectangle from opposite sides, so there is actually
+                        // an intersection but this would not count it as one. But for now we're
+                        // not considering this edge case...
+                        false
+                    }
+                }
+            })
+        });
+
+    rectangle_is_inside_boundary && !any_boundary_lines_intersect_rectangle
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -93,6 +189,20 @@ impl Point {
     }
 }
 
+impl FromStr for Point {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or("expected two comma-separated coordinates")?;
+        Ok(Point::new(
+            parse_num(x, "x coordinate")?,
+            parse_num(y, "y coordinate")?,
+        ))
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Rectangle {
     corner_a: Point,
@@ -207,28 +317,37 @@ enum Intersection {
     PerpendicularTouching(Point),
 }
 
-struct BoundingPolygon {
+#[derive(Debug, Clone)]
+pub struct BoundingPolygon {
     points: Vec<Point>,
     cache: BTreeMap<Point, Bounding>,
 }
 
 impl BoundingPolygon {
     /// Points are ordered (e.g. each consecutive point is joined by a LineSegment)
-    fn new(points: Vec<Point>) -> Self {
+    fn new(points: Vec<Point>) -> Result<Self, AocError> {
         let polygon = Self {
             points,
             cache: BTreeMap::new(),
         };
 
         // Manually inspecting the input, we see that all line segments are either vertical or
-        // horizontal. Assert that this is actually the case, as we'll use this assumption later.
-        assert!(
-            polygon
-                .iter_line_segments()
-                .all(|seg| seg.is_vertical() || seg.is_horizontal())
-        );
+        // horizontal. Check that this is actually the case, as we'll use this assumption later.
+        if let Some((i, seg)) = polygon
+            .iter_line_segments()
+            .enumerate()
+            .find(|(_, seg)| !seg.is_vertical() && !seg.is_horizontal())
+        {
+            // Segment i ends at point i + 1, which wraps around to the first point
+            let line = (i + 1) % polygon.points.len() + 1;
+            return Err(AocError::parse(
+                line,
+                &format!("{},{}", seg.b.x, seg.b.y),
+                "edge to this point is not horizontal or vertical",
+            ));
+        }
 
-        polygon
+        Ok(polygon)
     }
 
     fn iter_line_segments(&self) -> impl Iterator<Item = LineSegment<'_>> {
@@ -254,18 +373,23 @@ impl BoundingPolygon {
         // of the polygon an odd number of times.
         let mut crossing_count = 0;
         for seg in self.iter_line_segments() {
+            // The ends of each segment are included here, as a point on either end is on the
+            // boundary (unlike when counting crossings below)
             if seg.is_horizontal() {
-                let x_range_intersects = (seg.a.x > point.x) != (seg.b.x > point.x);
-                if point.y == seg.a.y && x_range_intersects {
+                let x_range_includes =
+                    seg.a.x.min(seg.b.x) <= point.x && point.x <= seg.a.x.max(seg.b.x);
+                if point.y == seg.a.y && x_range_includes {
                     return Bounding::OnBoundary;
                 }
                 continue;
             }
 
             let y_range_intersects = (seg.a.y > point.y) != (seg.b.y > point.y);
+            let y_range_includes =
+                seg.a.y.min(seg.b.y) <= point.y && point.y <= seg.a.y.max(seg.b.y);
             let x_to_left = point.x < seg.a.x;
 
-            if point.x == seg.a.x && y_range_intersects {
+            if point.x == seg.a.x && y_range_includes {
                 return Bounding::OnBoundary;
             }
 
@@ -282,6 +406,80 @@ impl BoundingPolygon {
     }
 }
 
+/// Every tile in the bounding box of the red tiles, and whether it's red or green
+struct Tiles {
+    min: Point,
+    width: usize,
+    filled: Vec<bool>,
+}
+
+impl Tiles {
+    fn new(points: &[Point]) -> Result<Self, AocError> {
+        let min = Point::new(
+            points.iter().map(|p| p.x).min().unwrap_or_default(),
+            points.iter().map(|p| p.y).min().unwrap_or_default(),
+        );
+        let max = Point::new(
+            points.iter().map(|p| p.x).max().unwrap_or_default(),
+            points.iter().map(|p| p.y).max().unwrap_or_default(),
+        );
+        let width = (max.x - min.x + 1) as usize;
+        let height = (max.y - min.y + 1) as usize;
+        if width.saturating_mul(height) > NAIVE_TILE_LIMIT {
+            return Err(AocError::unsolvable("too many tiles for the naive solver"));
+        }
+
+        let mut tiles = Tiles {
+            min: min.clone(),
+            width,
+            filled: vec![false; width * height],
+        };
+
+        let segments: Vec<(&Point, &Point)> = points.iter().circular_tuple_windows().collect();
+        for &(a, b) in &segments {
+            for x in a.x.min(b.x)..=a.x.max(b.x) {
+                for y in a.y.min(b.y)..=a.y.max(b.y) {
+                    let i = tiles.index(x, y);
+                    tiles.filled[i] = true;
+                }
+            }
+        }
+
+        // Fill in each row by counting the vertical edges crossed from the left. An edge covers
+        // the rows from its lower end up to but not including its upper end, so that a row
+        // passing through a corner is only counted once for each edge that carries on past it.
+        for y in min.y..=max.y {
+            let mut crossings: Vec<i64> = segments
+                .iter()
+                .filter(|(a, b)| a.x == b.x && a.y.min(b.y) <= y && y < a.y.max(b.y))
+                .map(|(a, _)| a.x)
+                .collect();
+            crossings.sort_unstable();
+
+            for x in min.x..=max.x {
+                let crossed = crossings.iter().filter(|&&edge| edge < x).count();
+                if crossed % 2 == 1 {
+                    let i = tiles.index(x, y);
+                    tiles.filled[i] = true;
+                }
+            }
+        }
+
+        Ok(tiles)
+    }
+
+    fn index(&self, x: i64, y: i64) -> usize {
+        (y - self.min.y) as usize * self.width + (x - self.min.x) as usize
+    }
+
+    /// Whether every tile in the rectangle is red or green
+    fn covers(&self, rectangle: &Rectangle) -> bool {
+        let (a, b) = (&rectangle.corner_a, &rectangle.corner_b);
+        (a.x.min(b.x)..=a.x.max(b.x))
+            .all(|x| (a.y.min(b.y)..=a.y.max(b.y)).all(|y| self.filled[self.index(x, y)]))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Bounding {
     Inside,
@@ -289,17 +487,217 @@ enum Bounding {
     OnBoundary,
 }
 
+/// Check that every line is a red tile like `7,1`, with no tile listed twice, and that the edge to
+/// each tile from the one before (wrapping around to the first) is horizontal or vertical
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut points = Vec::new();
+    let mut seen = BTreeMap::new();
+    for (n, line) in validate::lines(input) {
+        let Some((x, y)) = line.split_once(',') else {
+            violations.push(Violation::line(n, "expected a tile like 7,1"));
+            continue;
+        };
+        let x = validate::number::<i64>(&mut violations, n, line, x, "x coordinate");
+        let y = validate::number::<i64>(&mut violations, n, line, y, "y coordinate");
+        if let (Some(x), Some(y)) = (x, y) {
+            if let Some(first) = seen.insert((x, y), n) {
+                violations.push(Violation::line(n, format!("same tile as line {first}")));
+            }
+            points.push((n, x, y));
+        }
+    }
+
+    // Edges can only be checked when every tile could be read, or they would join the wrong tiles
+    if violations.is_empty() {
+        for (&(_, ax, ay), &(n, bx, by)) in points.iter().circular_tuple_windows() {
+            if ax != bx && ay != by {
+                violations.push(Violation::line(
+                    n,
+                    "edge to this tile is not horizontal or vertical",
+                ));
+            }
+        }
+        if points.len() < 2 {
+            violations.push(Violation::line(1, "need at least two red tiles"));
+        }
+    }
+    violations.sort_by_key(|violation| violation.line);
+    violations
+}
+
+/// A polygon with about `size` red tiles, shaped like a bar chart: a straight bottom edge, and a
+/// top edge that steps up and down between bars of random heights
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let bars = (size / 2).saturating_sub(1).max(1);
+    let spacing = (100_000 / bars).max(2);
+    let mut xs = vec![rng.range(1..=spacing)];
+    for _ in 0..bars {
+        xs.push(xs[xs.len() - 1] + rng.range(2..=spacing));
+    }
+
+    // Neighbouring bars have different heights, so that every red tile is a corner
+    let mut heights: Vec<usize> = Vec::new();
+    while heights.len() < bars {
+        let height = rng.range(2..=100_000);
+        if heights.last() != Some(&height) {
+            heights.push(height);
+        }
+    }
+
+    let mut corners = vec![(xs[0], 1), (xs[bars], 1)];
+    for i in (0..bars).rev() {
+        corners.push((xs[i + 1], heights[i]));
+        corners.push((xs[i], heights[i]));
+    }
+    corners.iter().map(|(x, y)| format!("{x},{y}\n")).collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::{BTreeSet, HashMap};
+
     use super::*;
+    use crate::{
+        generate::Rng,
+        testing::{check_against_reference, with_threads},
+    };
 
     const EXAMPLE_INPUT: &str = include_str!("../inputs/09.ex");
 
     #[test]
     fn test_example() {
-        let (pt1, pt2) = run_on_input(EXAMPLE_INPUT);
-        assert_eq!(50, pt1);
-        assert_eq!(24, pt2);
+        let polygon = Day09.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(50, part1(&polygon).unwrap());
+        assert_eq!(24, part2(&polygon).unwrap());
+    }
+
+    /// A generated input, with enough rectangles for the sort and the search to be split between
+    /// threads
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let polygon = Day09.parse(&generate(&mut Rng::new(9), 200)).unwrap();
+        assert_eq!(
+            part2(&polygon).unwrap(),
+            with_threads(|| part2_parallel(&polygon)).unwrap()
+        );
+    }
+
+    /// Polygons without any notches, so that nothing can intrude on a rectangle
+    #[test]
+    fn test_matches_naive() {
+        check_against_reference(&Day09, &Day09Naive, 500, |rng| random_polygon(rng, true));
+    }
+
+    /// The solver assumes that the boundary never intrudes on a rectangle without crossing it,
+    /// either with an intrusion of width 2 or by touching the rectangle from both sides. Run with
+    /// `--ignored` to see an input where this goes wrong.
+    #[test]
+    #[ignore = "the solver assumes the boundary never intrudes on a rectangle without crossing it"]
+    fn test_matches_naive_with_notches() {
+        check_against_reference(&Day09, &Day09Naive, 500, |rng| random_polygon(rng, false));
+    }
+
+    /// The corners of a random polygon, made by joining up squares of a small grid into a shape
+    /// without holes, and then spacing out the lines of the grid. If `convex` is set, every row
+    /// and column of the shape is in one piece, so there are no notches in the polygon.
+    fn random_polygon(rng: &mut Rng, convex: bool) -> String {
+        const GRID_SIZE: usize = 5;
+        let corners = loop {
+            let mut squares = BTreeSet::from([(
+                rng.range(0..=GRID_SIZE - 1) as i64,
+                rng.range(0..=GRID_SIZE - 1) as i64,
+            )]);
+            let square_count = rng.range(1..=12);
+            while squares.len() < square_count {
+                let &(x, y) = squares
+                    .iter()
+                    .nth(rng.range(0..=squares.len() - 1))
+                    .unwrap();
+                let (dx, dy) = [(1, 0), (-1, 0), (0, 1), (0, -1)][rng.range(0..=3)];
+                let (x, y) = (x + dx, y + dy);
+                if (0..GRID_SIZE as i64).contains(&x) && (0..GRID_SIZE as i64).contains(&y) {
+                    squares.insert((x, y));
+                }
+            }
+            if convex && !is_convex(&squares) {
+                continue;
+            }
+            if let Some(corners) = outline(&squares) {
+                break corners;
+            }
+        };
+
+        let lines = |rng: &mut Rng| {
+            let mut position = rng.range(0..=3);
+            (0..=GRID_SIZE)
+                .map(|_| {
+                    position += rng.range(1..=4);
+                    position
+                })
+                .collect::<Vec<_>>()
+        };
+        let (columns, rows) = (lines(rng), lines(rng));
+
+        corners
+            .iter()
+            .map(|&(x, y)| format!("{},{}\n", columns[x as usize], rows[y as usize]))
+            .collect()
+    }
+
+    /// Whether every row and column of the squares is in one piece
+    fn is_convex(squares: &BTreeSet<(i64, i64)>) -> bool {
+        let in_one_piece = |mut line: Vec<i64>| {
+            line.sort_unstable();
+            line.windows(2).all(|pair| pair[1] == pair[0] + 1)
+        };
+        squares.iter().all(|&(x, y)| {
+            in_one_piece(squares.iter().filter(|s| s.0 == x).map(|s| s.1).collect())
+                && in_one_piece(squares.iter().filter(|s| s.1 == y).map(|s| s.0).collect())
+        })
+    }
+
+    /// The corners of the outline of the squares in order, or None if the outline isn't a single
+    /// loop that only visits each corner once (the squares surround a hole, or only touch at a
+    /// corner)
+    fn outline(squares: &BTreeSet<(i64, i64)>) -> Option<Vec<(i64, i64)>> {
+        // Edges of each square that aren't shared with another square, going around the square
+        // in the same direction so that they join up into the outline
+        let mut edges = HashMap::new();
+        for &(x, y) in squares {
+            let sides = [
+                ((x, y - 1), (x, y), (x + 1, y)),
+                ((x + 1, y), (x + 1, y), (x + 1, y + 1)),
+                ((x, y + 1), (x + 1, y + 1), (x, y + 1)),
+                ((x - 1, y), (x, y + 1), (x, y)),
+            ];
+            for (neighbour, from, to) in sides {
+                if !squares.contains(&neighbour) && edges.insert(from, to).is_some() {
+                    return None;
+                }
+            }
+        }
+
+        let start = *edges.keys().next()?;
+        let mut points = vec![start];
+        let mut point = edges[&start];
+        while point != start {
+            points.push(point);
+            point = edges[&point];
+        }
+        if points.len() != edges.len() {
+            return None;
+        }
+
+        // Only keep the points where the outline turns
+        Some(
+            points
+                .iter()
+                .circular_tuple_windows()
+                .filter(|(a, b, c)| (b.0 - a.0, b.1 - a.1) != (c.0 - b.0, c.1 - b.1))
+                .map(|(_, b, _)| *b)
+                .collect(),
+        )
     }
 
     #[test]
@@ -312,12 +710,13 @@ mod tests {
 
     #[test]
     fn test_bounding_polygon() {
-        let polygon = BoundingPolygon::new(vec![
+        let mut polygon = BoundingPolygon::new(vec![
             Point::new(0, 0),
             Point::new(5, 0),
             Point::new(5, 5),
             Point::new(0, 5),
-        ]);
+        ])
+        .unwrap();
 
         assert_eq!(Bounding::Inside, polygon.contains(&Point::new(1, 1)));
         assert_eq!(Bounding::OnBoundary, polygon.contains(&Point::new(5, 3)));
@@ -332,7 +731,7 @@ mod tests {
         // 3.|..|...|...|.     3.|..|...|...|.
         // 4.+--+...+---+.     4.+--+...+---+.
         // 5..............     5..............
-        let polygon = BoundingPolygon::new(vec![
+        let mut polygon = BoundingPolygon::new(vec![
             Point::new(2, 1),
             Point::new(13, 1),
             Point::new(13, 4),
@@ -341,7 +740,8 @@ mod tests {
             Point::new(5, 2),
             Point::new(5, 4),
             Point::new(2, 4),
-        ]);
+        ])
+        .unwrap();
 
         assert_eq!(Bounding::Inside, polygon.contains(&Point::new(3, 3)));
         assert_eq!(Bounding::OnBoundary, polygon.contains(&Point::new(9, 4)));
@@ -350,7 +750,7 @@ mod tests {
 
     #[test]
     fn test_bounding_polygon_example_input() {
-        let polygon = BoundingPolygon::new(vec![
+        let mut polygon = BoundingPolygon::new(vec![
             Point::new(7, 1),
             Point::new(11, 1),
             Point::new(11, 7),
@@ -359,7 +759,8 @@ mod tests {
             Point::new(2, 5),
             Point::new(2, 3),
             Point::new(7, 3),
-        ]);
+        ])
+        .unwrap();
 
         assert_eq!(Bounding::Outside, polygon.contains(&Point::new(2, 7)));
     }
@@ -401,4 +802,31 @@ mod tests {
         assert_eq!(Intersection::NotIntersecting, a.intersects(&b));
         assert_eq!(Intersection::NotIntersecting, b.intersects(&a));
     }
+
+    #[test]
+    fn test_validate() {
+        let violations: Vec<String> = validate("1,1\n5,1\n5,x\n1,1\n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 3, column 3: unexpected 'x' in y coordinate \"x\"",
+                "line 4: same tile as line 1",
+            ],
+            violations
+        );
+
+        let violations: Vec<String> = validate("1,1\n5,1\n5,4\n3,6\n1,4\n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 4: edge to this tile is not horizontal or vertical",
+                "line 5: edge to this tile is not horizontal or vertical",
+            ],
+            violations
+        );
+    }
 }
@@ -1,34 +1,127 @@
-use std::{collections::BTreeMap, str::FromStr};
+use std::{
+    collections::{BTreeMap, HashSet},
+    str::FromStr,
+};
 
 use arrayvec::ArrayVec;
 use itertools::{EitherOrBoth, Itertools};
+use num::{One, Zero, integer::lcm, rational::Ratio};
+use rayon::prelude::*;
 
-use crate::Aoc;
-
-const INPUT: &str = include_str!("../inputs/10.in");
+use crate::{
+    Answer, Aoc, Solution,
+    error::{AocError, parse_lines, parse_num},
+    generate::Rng,
+    parallel,
+    validate::{self, Violation},
+};
 
 const MAX_ARRAY_LEN: usize = 10;
 
 pub struct Day10;
 
-impl Aoc for Day10 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+/// Tries every combination of buttons for part 1, and searches every reachable joltage for part 2,
+/// without relying on the parity argument of [`Day10`]. This is only practical for small joltages,
+/// and is used to check [`Day10`].
+pub struct Day10Naive;
+
+/// Solves part 2 as an integer linear program, by Gaussian elimination and then a search through
+/// the presses of the buttons that are left free. This doesn't rely on the parity argument of
+/// [`Day10`], and works however much a button adds to each joltage (a button that lists the same
+/// light more than once adds that many).
+pub struct Day10Linear;
+
+pub const ALTERNATIVES: &[(&str, &(dyn Aoc + Send + Sync))] =
+    &[("naive", &Day10Naive), ("linear", &Day10Linear)];
+
+/// The most buttons the naive solver will try every combination of
+const NAIVE_MAX_BUTTONS: usize = 20;
+
+/// The most joltage states the naive solver will search through for one machine
+const NAIVE_STATE_LIMIT: usize = 10_000_000;
+
+/// The most combinations of presses of the free buttons the linear solver will try for one machine
+const LINEAR_STEP_LIMIT: usize = 1_000_000;
+
+impl Solution for Day10 {
+    type Parsed = Vec<Machine>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_lines(input)
+    }
+
+    fn part1(&self, machines: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(machines)?.into())
+    }
+
+    fn part2(&self, machines: &Self::Parsed) -> Result<Answer, AocError> {
+        if parallel::enabled() {
+            Ok(part2_parallel(machines)?.into())
+        } else {
+            Ok(part2(machines)?.into())
+        }
     }
 }
 
-fn run_on_input(input: &str) -> (usize, usize) {
-    let machines: Vec<Machine> = input
-        .trim()
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect();
+impl Solution for Day10Naive {
+    type Parsed = Vec<Machine>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_lines(input)
+    }
+
+    fn part1(&self, machines: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(sum_per_machine(machines, Machine::fewest_toggles_naive)?.into())
+    }
+
+    fn part2(&self, machines: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(sum_per_machine(machines, Machine::fewest_presses_naive)?.into())
+    }
+}
+
+impl Solution for Day10Linear {
+    type Parsed = Vec<Machine>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_lines(input)
+    }
+
+    fn part1(&self, machines: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(machines)?.into())
+    }
 
-    let pt1 = machines
+    fn part2(&self, machines: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(sum_per_machine(machines, Machine::fewest_presses_linear)?.into())
+    }
+}
+
+/// Sum the fewest presses for each machine, where `solve` gives None if it's impossible
+fn sum_per_machine(
+    machines: &[Machine],
+    solve: impl Fn(&Machine) -> Result<Option<usize>, AocError>,
+) -> Result<usize, AocError> {
+    machines
         .iter()
-        .map(|machine| {
-            for i in 1.. {
+        .enumerate()
+        .map(|(i, machine)| {
+            solve(machine)?.ok_or_else(|| {
+                AocError::unsolvable(format!(
+                    "no combination of buttons works for the machine on line {}",
+                    i + 1
+                ))
+            })
+        })
+        .sum()
+}
+
+fn part1(machines: &[Machine]) -> Result<usize, AocError> {
+    machines
+        .iter()
+        .enumerate()
+        .map(|(line, machine)| {
+            // Pressing a button more than once is never useful (see below), so there's no point
+            // trying more presses than there are buttons.
+            for i in 1..=machine.buttons.0.len() {
                 // i is the number of button presses. Is it possible to make the light match with this
                 // number of presses?
                 if machine
@@ -54,28 +147,54 @@ fn run_on_input(input: &str) -> (usize, usize) {
                         false
                     })
                 {
-                    return i;
+                    return Ok(i);
                 }
             }
 
-            panic!("we hit max usize and still didn't find a combination that works...");
+            Err(AocError::unsolvable(format!(
+                "no combination of buttons matches the indicator lights for the machine on line {}",
+                line + 1
+            )))
         })
-        .sum();
+        .sum()
+}
 
-    let pt2 = machines.iter().map(|m| m.solve_pt2()).sum();
+fn part2(machines: &[Machine]) -> Result<usize, AocError> {
+    machines
+        .iter()
+        .enumerate()
+        .map(|(i, machine)| solve_machine_pt2(i, machine))
+        .sum()
+}
+
+/// Same as [`part2`], but solves the machines in parallel, as each is independent of the others
+fn part2_parallel(machines: &[Machine]) -> Result<usize, AocError> {
+    machines
+        .par_iter()
+        .enumerate()
+        .map(|(i, machine)| solve_machine_pt2(i, machine))
+        .sum()
+}
 
-    (pt1, pt2)
+/// Solve part 2 for the machine at index `i` of the input
+fn solve_machine_pt2(i: usize, machine: &Machine) -> Result<usize, AocError> {
+    machine.solve_pt2().ok_or_else(|| {
+        AocError::unsolvable(format!(
+            "no combination of buttons reaches the joltages for the machine on line {}",
+            i + 1
+        ))
+    })
 }
 
 #[derive(Debug, Clone)]
-struct Machine {
+pub struct Machine {
     indicators: IndicatorLights,
     joltages: Joltages,
     buttons: Buttons,
 }
 
 impl Machine {
-    fn solve_pt2(&self) -> usize {
+    fn solve_pt2(&self) -> Option<usize> {
         // This is all of the possible joltages that cna be produced by pressing each button at most
         // once, along with the number of button presses required to make that value.
         let pattern_costs = self
@@ -83,7 +202,682 @@ impl Machine {
             .possible_single_press_joltages(self.joltages.0.len());
 
         let mut cache = BTreeMap::new();
-        solve_single_recurse(self.joltages.clone(), &pattern_costs, &mut cache).unwrap()
+        solve_single_recurse(self.joltages.clone(), &pattern_costs, &mut cache)
+    }
+
+    /// Fewest presses to reach the joltages, found by minimising the total presses subject to
+    /// `increments × presses = joltages` with a whole, non-negative number of presses of each
+    /// button. Gaussian elimination gives the presses of some buttons in terms of the others, and
+    /// then the presses of those others are searched, skipping any that couldn't beat the best
+    /// found so far even if the buttons could be pressed a fractional number of times.
+    fn fewest_presses_linear(&self) -> Result<Option<usize>, AocError> {
+        self.search_linear(true)
+    }
+
+    /// [`Machine::fewest_presses_linear`], optionally without the relaxed bound, to check that
+    /// it's needed
+    fn search_linear(&self, relaxed: bool) -> Result<Option<usize>, AocError> {
+        let increments = self.increments();
+        let joltages: Vec<i64> = self.joltages.0.iter().map(|&j| j as i64).collect();
+        let Some(system) = ReducedSystem::new(&increments, &joltages) else {
+            return Ok(None);
+        };
+
+        let mut search = LinearSearch {
+            system: &system,
+            presses: vec![0; system.free.len()],
+            best: None,
+            steps: 0,
+            relaxed,
+        };
+        search.search(0)?;
+        Ok(search.best)
+    }
+
+    /// How much each button adds to each joltage, indexed by joltage then button. Buttons that
+    /// add the same to every joltage are only included once, since it doesn't matter which of
+    /// them is pressed.
+    fn increments(&self) -> Vec<Vec<i64>> {
+        let buttons: Vec<Vec<i64>> = self
+            .buttons
+            .0
+            .iter()
+            .map(|button| {
+                let mut increments = vec![0; self.joltages.0.len()];
+                for &j in &button.0 {
+                    increments[j] += 1;
+                }
+                increments
+            })
+            .unique()
+            .collect();
+        (0..self.joltages.0.len())
+            .map(|j| buttons.iter().map(|button| button[j]).collect())
+            .collect()
+    }
+
+    /// Fewest buttons to match the indicator lights, by trying every combination of buttons
+    fn fewest_toggles_naive(&self) -> Result<Option<usize>, AocError> {
+        let buttons = &self.buttons.0;
+        if buttons.len() > NAIVE_MAX_BUTTONS {
+            return Err(AocError::unsolvable(
+                "too many buttons for the naive solver",
+            ));
+        }
+
+        Ok((0..1u32 << buttons.len())
+            .filter(|combination| {
+                let mut lights = self.indicators.clone();
+                for (i, button) in buttons.iter().enumerate() {
+                    if combination & (1 << i) != 0 {
+                        lights.apply_button_press(button);
+                    }
+                }
+                lights.0.iter().all(|light| !light)
+            })
+            .map(|combination| combination.count_ones() as usize)
+            .min())
+    }
+
+    /// Fewest presses to reach the joltages, by a breadth first search from zero through every
+    /// joltage that doesn't overshoot
+    fn fewest_presses_naive(&self) -> Result<Option<usize>, AocError> {
+        let states = self.joltages.0.iter().try_fold(1usize, |states, &joltage| {
+            states
+                .checked_mul(joltage as usize + 1)
+                .filter(|&states| states <= NAIVE_STATE_LIMIT)
+        });
+        if states.is_none() {
+            return Err(AocError::unsolvable(
+                "joltages too large for the naive solver",
+            ));
+        }
+
+        let start = Joltages::new(self.joltages.0.len());
+        let mut seen = HashSet::from([start.clone()]);
+        let mut frontier = vec![start];
+        for presses in 0.. {
+            if frontier.is_empty() {
+                break;
+            }
+            if frontier.contains(&self.joltages) {
+                return Ok(Some(presses));
+            }
+
+            let mut next = Vec::new();
+            for joltages in &frontier {
+                for button in &self.buttons.0 {
+                    let mut pressed = joltages.clone();
+                    pressed.apply_button_press(button);
+                    let overshoots = pressed.0.iter().zip(&self.joltages.0).any(|(a, b)| a > b);
+                    if !overshoots && seen.insert(pressed.clone()) {
+                        next.push(pressed);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        Ok(None)
+    }
+}
+
+/// The joltage equations `increments × presses = joltages` after Gaussian elimination, with the
+/// presses of each pivot button given in terms of the presses of the free buttons
+struct ReducedSystem {
+    /// The buttons whose presses can be chosen freely. Those that change the total presses come
+    /// first, then those that don't, and within each the ones that can be pressed the fewest times
+    /// come first.
+    free: Vec<usize>,
+    /// How much each free button adds to each joltage, indexed by free button then joltage
+    increments: Vec<Vec<i64>>,
+    joltages: Vec<i64>,
+    pivots: Vec<PivotRow>,
+    /// The total presses times `scale` is `objective + Σ weights[i] × presses of free[i]`
+    scale: i64,
+    objective: i64,
+    weights: Vec<i64>,
+    /// The index of the first free button from which on none of the weights are non-zero
+    flat: usize,
+}
+
+/// One equation of a [`ReducedSystem`], scaled to integers, which says that the pivot button is
+/// pressed `(constant - Σ coefficients[i] × presses of free[i]) / denominator` times
+struct PivotRow {
+    denominator: i64,
+    constant: i64,
+    coefficients: Vec<i64>,
+    /// How much the pivot button adds to each joltage
+    increments: Vec<i64>,
+}
+
+impl ReducedSystem {
+    /// Reduce the equations, where `increments[j][b]` is how much button `b` adds to joltage `j`.
+    /// Returns None if they can't be solved even with fractional or negative presses.
+    fn new(increments: &[Vec<i64>], joltages: &[i64]) -> Option<Self> {
+        let buttons = increments.first().map_or(0, Vec::len);
+        // Each row is an equation, with the joltage in the last column
+        let mut matrix: Vec<Vec<Ratio<i64>>> = increments
+            .iter()
+            .zip(joltages)
+            .map(|(row, &joltage)| {
+                row.iter()
+                    .chain(std::iter::once(&joltage))
+                    .map(|&n| Ratio::from_integer(n))
+                    .collect()
+            })
+            .collect();
+
+        let mut pivot_columns = Vec::new();
+        for column in 0..buttons {
+            let row = pivot_columns.len();
+            let Some(pivot_row) = (row..matrix.len()).find(|&r| !matrix[r][column].is_zero())
+            else {
+                continue;
+            };
+            matrix.swap(row, pivot_row);
+            let pivot = matrix[row][column];
+            for value in &mut matrix[row] {
+                *value /= pivot;
+            }
+            for other in 0..matrix.len() {
+                let factor = matrix[other][column];
+                if other != row && !factor.is_zero() {
+                    let pivot_row = matrix[row].clone();
+                    for (value, pivot_value) in matrix[other].iter_mut().zip(pivot_row).skip(column)
+                    {
+                        *value -= factor * pivot_value;
+                    }
+                }
+            }
+            pivot_columns.push(column);
+        }
+
+        // The rows without a pivot are all zero apart from the joltage, which must be zero too
+        if matrix[pivot_columns.len()..]
+            .iter()
+            .any(|row| !row[buttons].is_zero())
+        {
+            return None;
+        }
+
+        let most_presses = |button: usize| {
+            (0..joltages.len())
+                .filter(|&j| increments[j][button] > 0)
+                .map(|j| joltages[j] / increments[j][button])
+                .min()
+                .unwrap_or(0)
+        };
+        let free: Vec<usize> = (0..buttons)
+            .filter(|column| !pivot_columns.contains(column))
+            .collect();
+
+        let pivots: Vec<PivotRow> = matrix
+            .iter()
+            .zip(&pivot_columns)
+            .map(|(row, &pivot)| {
+                let values: Vec<Ratio<i64>> = std::iter::once(row[buttons])
+                    .chain(free.iter().map(|&column| row[column]))
+                    .collect();
+                let denominator = values
+                    .iter()
+                    .fold(1, |denominator, value| lcm(denominator, *value.denom()));
+                let mut scaled = values
+                    .iter()
+                    .map(|value| (value * denominator).to_integer());
+                PivotRow {
+                    denominator,
+                    constant: scaled.next().unwrap(),
+                    coefficients: scaled.collect(),
+                    increments: increments.iter().map(|row| row[pivot]).collect(),
+                }
+            })
+            .collect();
+
+        // Each free button adds one press to the total, and changes the presses of the pivots
+        let scale = pivots
+            .iter()
+            .fold(1, |scale, row| lcm(scale, row.denominator));
+        let objective = pivots
+            .iter()
+            .map(|row| row.constant * (scale / row.denominator))
+            .sum();
+        let weights: Vec<i64> = (0..free.len())
+            .map(|i| {
+                scale
+                    - pivots
+                        .iter()
+                        .map(|row| row.coefficients[i] * (scale / row.denominator))
+                        .sum::<i64>()
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..free.len()).collect();
+        order.sort_by_key(|&i| (weights[i] == 0, most_presses(free[i])));
+        let flat = order
+            .iter()
+            .position(|&i| weights[i] == 0)
+            .unwrap_or(free.len());
+        Some(ReducedSystem {
+            free: order.iter().map(|&i| free[i]).collect(),
+            increments: order
+                .iter()
+                .map(|&i| increments.iter().map(|row| row[free[i]]).collect())
+                .collect(),
+            joltages: joltages.to_vec(),
+            pivots: pivots
+                .into_iter()
+                .map(|row| PivotRow {
+                    coefficients: order.iter().map(|&i| row.coefficients[i]).collect(),
+                    ..row
+                })
+                .collect(),
+            scale,
+            objective,
+            weights: order.iter().map(|&i| weights[i]).collect(),
+            flat,
+        })
+    }
+}
+
+/// The range of `coefficient × presses` for presses in `range`
+fn span(coefficient: i64, (low, high): (i64, i64)) -> (i64, i64) {
+    let (a, b) = (coefficient * low, coefficient * high);
+    (a.min(b), a.max(b))
+}
+
+/// `numerator / denominator` rounded up, for a positive denominator
+fn ceil_div(numerator: i64, denominator: i64) -> i64 {
+    -(-numerator).div_euclid(denominator)
+}
+
+/// Exact fractions for the relaxation, wider than the system's so the tableau has room to grow
+type Rational = Ratio<i128>;
+
+/// The least `Σ weights[i] × presses of free[i]` over the free buttons from the `free`th onwards
+/// (times the scale), if they could be pressed a fractional number of times within their `ranges`
+/// while keeping each pivot row within its `limits`. Returns None if that's impossible.
+fn relaxed_minimum(
+    system: &ReducedSystem,
+    free: usize,
+    limits: &[(i64, i64)],
+    ranges: &[(i64, i64)],
+) -> Option<Rational> {
+    let rational = |value: i64| Rational::from_integer(value.into());
+    // Count the presses from the bottom of each range, so that they can't be negative
+    let columns = ranges.len();
+    let mut constraints = Vec::new();
+    for (row, &(least, greatest)) in system.pivots.iter().zip(limits) {
+        let coefficients: Vec<Rational> = row.coefficients[free..]
+            .iter()
+            .map(|&coefficient| rational(coefficient))
+            .collect();
+        let offset: i64 = row.coefficients[free..]
+            .iter()
+            .zip(ranges)
+            .map(|(coefficient, (low, _))| coefficient * low)
+            .sum();
+        let negated = coefficients
+            .iter()
+            .map(|coefficient| -coefficient)
+            .collect();
+        constraints.push((coefficients, rational(greatest - offset)));
+        constraints.push((negated, rational(offset - least)));
+    }
+    for (i, (low, high)) in ranges.iter().enumerate() {
+        let mut coefficients = vec![Rational::zero(); columns];
+        coefficients[i] = Rational::one();
+        constraints.push((coefficients, rational(high - low)));
+    }
+
+    let weights = &system.weights[free..];
+    let objective: Vec<Rational> = weights.iter().map(|&weight| rational(-weight)).collect();
+    let offset: i64 = weights
+        .iter()
+        .zip(ranges)
+        .map(|(weight, (low, _))| weight * low)
+        .sum();
+    Simplex::new(&constraints, &objective)
+        .maximise()
+        .map(|maximum| rational(offset) - maximum)
+}
+
+/// Which row of a [`Simplex`] tableau to improve
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Goal {
+    /// Bring the extra variable up to zero, which gives a starting point meeting every constraint
+    Feasible,
+    /// Maximise the objective, starting from a point that meets every constraint
+    Optimal,
+}
+
+/// A simplex tableau for maximising `objective · x` subject to `constraints · x ≤ limit` and
+/// `x ≥ 0`, following the usual dictionary form with an extra variable to find a feasible
+/// starting point when some limit is negative
+struct Simplex {
+    /// Which variable is basic in each constraint row, where the variables are numbered with the
+    /// original ones first, then a slack variable for each constraint, and `None` is the extra one
+    basic: Vec<Option<usize>>,
+    non_basic: Vec<Option<usize>>,
+    /// The constraint rows, then the objective row, then the row used to find a feasible start.
+    /// The column after the non-basic variables holds the value of each row.
+    table: Vec<Vec<Rational>>,
+}
+
+impl Simplex {
+    fn new(constraints: &[(Vec<Rational>, Rational)], objective: &[Rational]) -> Self {
+        let (rows, columns) = (constraints.len(), objective.len());
+        let mut table = vec![vec![Rational::zero(); columns + 2]; rows + 2];
+        for (row, (coefficients, limit)) in table.iter_mut().zip(constraints) {
+            row[..columns].clone_from_slice(coefficients);
+            row[columns] = -Rational::one();
+            row[columns + 1] = *limit;
+        }
+        for (j, value) in objective.iter().enumerate() {
+            table[rows][j] = -value;
+        }
+        table[rows + 1][columns] = Rational::one();
+        Simplex {
+            basic: (columns..columns + rows).map(Some).collect(),
+            non_basic: (0..columns)
+                .map(Some)
+                .chain(std::iter::once(None))
+                .collect(),
+            table,
+        }
+    }
+
+    fn rows(&self) -> usize {
+        self.basic.len()
+    }
+
+    fn columns(&self) -> usize {
+        self.non_basic.len() - 1
+    }
+
+    /// Swap the basic variable of row `r` with the non-basic variable of column `s`
+    fn pivot(&mut self, r: usize, s: usize) {
+        let pivot_row = self.table[r].clone();
+        let inverse = pivot_row[s].recip();
+        for (i, row) in self.table.iter_mut().enumerate() {
+            if i != r && !row[s].is_zero() {
+                let factor = row[s] * inverse;
+                for (value, pivot_value) in row.iter_mut().zip(&pivot_row) {
+                    *value -= pivot_value * factor;
+                }
+                row[s] = -factor;
+            }
+        }
+        for value in self.table[r].iter_mut() {
+            *value *= inverse;
+        }
+        self.table[r][s] = inverse;
+        std::mem::swap(&mut self.basic[r], &mut self.non_basic[s]);
+    }
+
+    /// Pivot until the row for the `goal` can't be improved: the feasibility row (only while the
+    /// extra variable is in use) or the objective row. Returns false if it's unbounded.
+    fn run(&mut self, goal: Goal) -> bool {
+        let (rows, columns) = (self.rows(), self.columns());
+        let row = match goal {
+            Goal::Optimal => rows,
+            Goal::Feasible => rows + 1,
+        };
+        loop {
+            // Bland's rule stops it from cycling: the lowest numbered variable that improves the
+            // row enters (the extra one counting as lowest, and only entering while finding a
+            // feasible start), and ties for which leaves go to the lowest numbered one too
+            let entering = (0..=columns)
+                .filter(|&j| goal == Goal::Feasible || self.non_basic[j].is_some())
+                .filter(|&j| self.table[row][j] < Rational::zero())
+                .min_by_key(|&j| self.non_basic[j]);
+            let Some(s) = entering else {
+                return true;
+            };
+            let leaving = (0..rows)
+                .filter(|&i| self.table[i][s] > Rational::zero())
+                .min_by_key(|&i| (self.table[i][columns + 1] / self.table[i][s], self.basic[i]));
+            let Some(r) = leaving else {
+                return false;
+            };
+            self.pivot(r, s);
+        }
+    }
+
+    /// The maximum of the objective, or None if no `x` satisfies the constraints
+    fn maximise(mut self) -> Option<Rational> {
+        let (rows, columns) = (self.rows(), self.columns());
+        let most_negative = (0..rows).min_by_key(|&i| self.table[i][columns + 1])?;
+        if self.table[most_negative][columns + 1] < Rational::zero() {
+            self.pivot(most_negative, columns);
+            if !self.run(Goal::Feasible) || self.table[rows + 1][columns + 1] < Rational::zero() {
+                return None;
+            }
+            // Swap the extra variable out if it's still basic, which it can be at zero
+            if let Some(r) = (0..rows).find(|&i| self.basic[i].is_none())
+                && let Some(s) = (0..columns).find(|&j| !self.table[r][j].is_zero())
+            {
+                self.pivot(r, s);
+            }
+        }
+        // The problem is bounded, since every variable has an upper limit
+        self.run(Goal::Optimal);
+        Some(self.table[rows][columns + 1])
+    }
+}
+
+/// The state of the branch and bound search through the presses of the free buttons of a
+/// [`ReducedSystem`]
+struct LinearSearch<'a> {
+    system: &'a ReducedSystem,
+    /// The presses chosen so far for each free button
+    presses: Vec<i64>,
+    best: Option<usize>,
+    steps: usize,
+    /// Whether to cut off presses using [`relaxed_minimum`]
+    relaxed: bool,
+}
+
+impl LinearSearch<'_> {
+    /// Try every number of presses of the free buttons from the `free`th onwards that could lead
+    /// to fewer presses than the best found so far. Returns whether any of them worked.
+    fn search(&mut self, free: usize) -> Result<bool, AocError> {
+        self.steps += 1;
+        if self.steps > LINEAR_STEP_LIMIT {
+            return Err(AocError::unsolvable(
+                "too many combinations of free buttons for the linear solver",
+            ));
+        }
+
+        let system = self.system;
+        if free == system.free.len() {
+            let total = self.total();
+            if let Some(total) = total {
+                self.best = Some(self.best.map_or(total, |best| best.min(total)));
+            }
+            return Ok(total.is_some());
+        }
+
+        // The most each of the remaining free buttons can be pressed without overshooting the
+        // joltages left over from the presses chosen so far
+        let mut remaining = system.joltages.clone();
+        for (increments, presses) in system.increments.iter().zip(&self.presses).take(free) {
+            for (joltage, increment) in remaining.iter_mut().zip(increments) {
+                *joltage -= increment * presses;
+            }
+        }
+        let most: Vec<i64> = system.increments[free..]
+            .iter()
+            .map(|increments| {
+                increments
+                    .iter()
+                    .zip(&remaining)
+                    .filter(|(increment, _)| **increment > 0)
+                    .map(|(increment, joltage)| joltage / increment)
+                    .min()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let limits = self.limits(free, &remaining);
+        let Some(ranges) = self.ranges(free, &limits, most) else {
+            return Ok(false);
+        };
+        let (low, high) = ranges[0];
+
+        // Allowing fractional presses of the free buttons left gives a bound on the total which
+        // takes every pivot button into account at once
+        let chosen_weight: i64 = (0..free).map(|i| system.weights[i] * self.presses[i]).sum();
+        if self.relaxed {
+            let Some(relaxed) = relaxed_minimum(system, free, &limits, &ranges) else {
+                return Ok(false);
+            };
+            if let Some(best) = self.best {
+                let relaxed =
+                    Rational::from_integer((system.objective + chosen_weight).into()) + relaxed;
+                let least = (relaxed / Rational::from_integer(system.scale.into())).ceil();
+                if least >= Rational::from_integer(best as i128) {
+                    return Ok(false);
+                }
+            }
+        }
+
+        // The least the total presses (times the scale) could be with the presses chosen so far
+        let bound = system.objective
+            + chosen_weight
+            + ranges[1..]
+                .iter()
+                .zip(&system.weights[free + 1..])
+                .map(|(&(low, high), weight)| (weight * low).min(weight * high))
+                .sum::<i64>();
+        let weight = system.weights[free];
+        let mut found = false;
+        // Try the presses that make the total smallest first, so that later ones can be cut off
+        let order: Box<dyn Iterator<Item = i64>> = if weight < 0 {
+            Box::new((low..=high).rev())
+        } else {
+            Box::new(low..=high)
+        };
+        for presses in order {
+            if let Some(best) = self.best
+                && bound + weight * presses >= system.scale * best as i64
+            {
+                break;
+            }
+            self.presses[free] = presses;
+            found |= self.search(free + 1)?;
+            // The rest of the free buttons don't change the total, so one way of pressing them
+            // is as good as any other
+            if found && free >= system.flat {
+                break;
+            }
+        }
+        self.presses[free] = 0;
+        Ok(found)
+    }
+
+    /// The range of `Σ coefficients[i] × presses of free[i]` over the free buttons from the
+    /// `free`th onwards for each pivot row, so that its pivot button is pressed between zero and
+    /// the most that doesn't overshoot the `remaining` joltages
+    fn limits(&self, free: usize, remaining: &[i64]) -> Vec<(i64, i64)> {
+        self.system
+            .pivots
+            .iter()
+            .map(|row| {
+                let chosen: i64 = (0..free)
+                    .map(|i| row.coefficients[i] * self.presses[i])
+                    .sum();
+                let pivot_most = row
+                    .increments
+                    .iter()
+                    .zip(remaining)
+                    .filter(|(increment, _)| **increment > 0)
+                    .map(|(increment, joltage)| joltage / increment)
+                    .min()
+                    .unwrap_or(0);
+                let high = row.constant - chosen;
+                (high - row.denominator * pivot_most, high)
+            })
+            .collect()
+    }
+
+    /// The range of presses of each free button from the `free`th onwards that could still work,
+    /// starting from zero up to its `most`. The `limits` of each pivot row limit each free button
+    /// however the others are pressed, and narrowing one free button's range can narrow the
+    /// others. Returns None if some range is empty.
+    fn ranges(
+        &self,
+        free: usize,
+        limits: &[(i64, i64)],
+        most: Vec<i64>,
+    ) -> Option<Vec<(i64, i64)>> {
+        let system = self.system;
+        let mut ranges: Vec<(i64, i64)> = most.into_iter().map(|most| (0, most)).collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (row, &(least, greatest)) in system.pivots.iter().zip(limits) {
+                let coefficients = &row.coefficients[free..];
+                let (min_sum, max_sum) = coefficients
+                    .iter()
+                    .zip(&ranges)
+                    .map(|(&coefficient, &range)| span(coefficient, range))
+                    .fold((0, 0), |(low, high), (a, b)| (low + a, high + b));
+                if min_sum > greatest || max_sum < least {
+                    return None;
+                }
+                for i in 0..ranges.len() {
+                    let coefficient = coefficients[i];
+                    if coefficient == 0 {
+                        continue;
+                    }
+                    // coefficient × presses is between `at_least` and `at_most`
+                    let (low, high) = span(coefficient, ranges[i]);
+                    let at_most = greatest - (min_sum - low);
+                    let at_least = least - (max_sum - high);
+                    let (mut new_low, mut new_high) = if coefficient > 0 {
+                        (
+                            ceil_div(at_least, coefficient),
+                            at_most.div_euclid(coefficient),
+                        )
+                    } else {
+                        (
+                            ceil_div(-at_most, -coefficient),
+                            (-at_least).div_euclid(-coefficient),
+                        )
+                    };
+                    new_low = new_low.max(ranges[i].0);
+                    new_high = new_high.min(ranges[i].1);
+                    if new_low > new_high {
+                        return None;
+                    }
+                    if (new_low, new_high) != ranges[i] {
+                        ranges[i] = (new_low, new_high);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        Some(ranges)
+    }
+
+    /// The total presses with the presses of the free buttons chosen, or None if a pivot button
+    /// would need a negative or fractional number of presses
+    fn total(&self) -> Option<usize> {
+        let mut total: i64 = self.presses.iter().sum();
+        for row in &self.system.pivots {
+            let numerator = row.constant
+                - row
+                    .coefficients
+                    .iter()
+                    .zip(&self.presses)
+                    .map(|(coefficient, presses)| coefficient * presses)
+                    .sum::<i64>();
+            if numerator < 0 || numerator % row.denominator != 0 {
+                return None;
+            }
+            total += numerator / row.denominator;
+        }
+        Some(total as usize)
     }
 }
 
@@ -145,55 +939,68 @@ fn solve_single_recurse(
 }
 
 impl FromStr for Machine {
-    type Err = ();
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut parts = s.split_whitespace();
 
-        let indicators = IndicatorLights(
-            parts
-                .next()
-                .unwrap()
-                .strip_prefix('[')
-                .unwrap()
-                .strip_suffix(']')
-                .unwrap()
-                .as_bytes()
-                .iter()
-                .map(|c| *c == b'#')
-                .collect(),
-        );
+        let indicators_str = parts
+            .next()
+            .and_then(|part| part.strip_prefix('['))
+            .and_then(|part| part.strip_suffix(']'))
+            .ok_or("expected indicator lights in [square brackets] first")?;
+        let mut indicators = ArrayVec::new();
+        for c in indicators_str.bytes() {
+            indicators
+                .try_push(c == b'#')
+                .map_err(|_| format!("more than {MAX_ARRAY_LEN} indicator lights"))?;
+        }
+        let light_count = indicators.len();
 
         let mut buttons = Vec::new();
         let mut joltages = ArrayVec::new();
 
         for part in parts {
-            if part.starts_with('(') {
-                let button = part
-                    .strip_prefix('(')
-                    .unwrap()
+            if let Some(button_str) = part.strip_prefix('(') {
+                let button_str = button_str
                     .strip_suffix(')')
-                    .unwrap()
-                    .split(',')
-                    .map(|n| n.parse().unwrap())
-                    .collect();
+                    .ok_or_else(|| format!("unclosed button {part:?}"))?;
+                let mut button = ArrayVec::new();
+                for n in button_str.split(',') {
+                    let index: usize = parse_num(n, "button light index")?;
+                    if index >= light_count {
+                        return Err(format!(
+                            "button {part:?} refers to light {index} but there are only \
+                            {light_count} lights"
+                        ));
+                    }
+                    button
+                        .try_push(index)
+                        .map_err(|_| format!("button {part:?} has too many lights"))?;
+                }
                 buttons.push(Button(button));
             } else {
-                for joltage in part
+                let joltages_str = part
                     .strip_prefix('{')
-                    .unwrap()
-                    .strip_suffix('}')
-                    .unwrap()
-                    .split(',')
-                    .map(|n| n.parse().unwrap())
-                {
-                    joltages.push(joltage);
+                    .and_then(|part| part.strip_suffix('}'))
+                    .ok_or_else(|| format!("unexpected {part:?}, expected a button or joltages"))?;
+                for joltage in joltages_str.split(',') {
+                    joltages
+                        .try_push(parse_num(joltage, "joltage")?)
+                        .map_err(|_| format!("more than {MAX_ARRAY_LEN} joltages"))?;
                 }
             }
         }
 
+        if joltages.len() != light_count {
+            return Err(format!(
+                "found {} joltages but there are {light_count} lights",
+                joltages.len()
+            ));
+        }
+
         Ok(Machine {
-            indicators,
+            indicators: IndicatorLights(indicators),
             joltages: Joltages(joltages),
             buttons: Buttons(buttons),
         })
@@ -298,7 +1105,7 @@ impl std::ops::Sub<Joltages> for Joltages {
     fn sub(mut self, rhs: Joltages) -> Self::Output {
         assert!(self.0.len() >= rhs.0.len());
 
-        for (n, other) in self.0.iter_mut().zip(rhs.0.into_iter()) {
+        for (n, other) in self.0.iter_mut().zip(rhs.0) {
             if other > *n {
                 return None;
             } else {
@@ -310,17 +1117,228 @@ impl std::ops::Sub<Joltages> for Joltages {
     }
 }
 
+/// Check that every line is a machine like `[.##.] (3) (1,3) {3,5,4,7}`: at most
+/// `MAX_ARRAY_LEN` indicator lights, then at least one button listing at most `MAX_ARRAY_LEN`
+/// light indices, then a joltage for each light. A button may list a light more than once, adding
+/// that much to its joltage.
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for (n, line) in validate::lines(input) {
+        let mut tokens = line.split_whitespace();
+        let Some(lights) = tokens
+            .next()
+            .and_then(|token| token.strip_prefix('['))
+            .and_then(|token| token.strip_suffix(']'))
+        else {
+            violations.push(Violation::line(
+                n,
+                "expected indicator lights in [square brackets] first",
+            ));
+            continue;
+        };
+        if let Some((i, c)) = lights
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !matches!(c, '.' | '#'))
+        {
+            violations.push(Violation::at(
+                n,
+                validate::column(line, lights) + i,
+                format!("unexpected {c:?} in indicator lights"),
+            ));
+        }
+        let light_count = lights.len();
+        if light_count > MAX_ARRAY_LEN {
+            violations.push(Violation::at(
+                n,
+                validate::column(line, lights),
+                format!(
+                    "{light_count} indicator lights, but at most {MAX_ARRAY_LEN} are supported"
+                ),
+            ));
+        }
+
+        let mut button_count = 0;
+        let mut joltages_seen = false;
+        for token in tokens {
+            let column = validate::column(line, token);
+            if joltages_seen {
+                violations.push(Violation::at(
+                    n,
+                    column,
+                    "unexpected text after the joltages",
+                ));
+                break;
+            }
+            if let Some(indices) = token.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+                button_count += 1;
+                let index_count = indices.split(',').count();
+                if index_count > MAX_ARRAY_LEN {
+                    violations.push(Violation::at(
+                        n,
+                        column,
+                        format!(
+                            "button lists {index_count} lights, but at most {MAX_ARRAY_LEN} are \
+                            supported"
+                        ),
+                    ));
+                }
+                for index in indices.split(',') {
+                    let Some(i) =
+                        validate::number::<usize>(&mut violations, n, line, index, "light index")
+                    else {
+                        continue;
+                    };
+                    let column = validate::column(line, index);
+                    if i >= light_count {
+                        violations.push(Violation::at(
+                            n,
+                            column,
+                            format!("light {i} doesn't exist, there are {light_count} lights"),
+                        ));
+                    }
+                }
+            } else if let Some(joltages) = token.strip_prefix('{').and_then(|t| t.strip_suffix('}'))
+            {
+                joltages_seen = true;
+                let joltages: Vec<&str> = joltages.split(',').collect();
+                for joltage in &joltages {
+                    validate::number::<u16>(&mut violations, n, line, joltage, "joltage");
+                }
+                if joltages.len() != light_count {
+                    violations.push(Violation::at(
+                        n,
+                        column,
+                        format!(
+                            "{} joltages, but there are {light_count} lights",
+                            joltages.len()
+                        ),
+                    ));
+                }
+            } else {
+                violations.push(Violation::at(
+                    n,
+                    column,
+                    format!("unexpected {token:?}, expected a button or joltages"),
+                ));
+            }
+        }
+
+        if button_count == 0 {
+            violations.push(Violation::line(n, "machine has no buttons"));
+        }
+        if !joltages_seen {
+            violations.push(Violation::line(
+                n,
+                "expected joltages in {curly brackets} last",
+            ));
+        }
+    }
+    violations
+}
+
+/// `size` machines with up to 10 lights and 13 buttons, as in the puzzle input
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| generate_machine(rng, MAX_ARRAY_LEN, 13, 20, 1))
+        .collect()
+}
+
+/// A random machine, as a line of input. The lights and joltages are made by pressing the
+/// buttons, with each pressed up to `max_presses` times, so that they can always be reached. As in
+/// the puzzle input, at least one light is on.
+///
+/// With a `max_increment` above 1, a button can list each light after its first up to that many
+/// times, adding that much to its joltage.
+fn generate_machine(
+    rng: &mut Rng,
+    max_lights: usize,
+    max_buttons: usize,
+    max_presses: usize,
+    max_increment: usize,
+) -> String {
+    let light_count = rng.range(2..=max_lights);
+    let buttons: Vec<Vec<usize>> = (0..rng.range(2..=max_buttons))
+        .map(|_| {
+            let button: Vec<usize> = (0..light_count).filter(|_| rng.chance(1, 3)).collect();
+            if button.is_empty() {
+                return vec![rng.range(0..=light_count - 1)];
+            }
+            if max_increment == 1 {
+                return button;
+            }
+            // The first light is only listed once, so that every button toggles a light
+            let mut repeated = vec![button[0]];
+            for &i in &button[1..] {
+                let increment = rng.range(1..=max_increment);
+                repeated.extend(
+                    (0..increment)
+                        .map(|_| i)
+                        .take(MAX_ARRAY_LEN - repeated.len()),
+                );
+            }
+            repeated
+        })
+        .collect();
+
+    let mut lights = vec![false; light_count];
+    while lights.iter().all(|light| !light) {
+        for button in &buttons {
+            if rng.chance(1, 2) {
+                for &i in button {
+                    lights[i] = !lights[i];
+                }
+            }
+        }
+    }
+
+    let mut joltages = vec![0; light_count];
+    for button in &buttons {
+        let presses = rng.range(0..=max_presses);
+        for &i in button {
+            joltages[i] += presses;
+        }
+    }
+
+    let lights: String = lights
+        .iter()
+        .map(|&on| if on { '#' } else { '.' })
+        .collect();
+    let buttons = buttons
+        .iter()
+        .map(|button| format!("({})", button.iter().join(",")));
+    format!(
+        "[{lights}] {} {{{}}}\n",
+        buttons.format(" "),
+        joltages.iter().join(",")
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        generate::Rng,
+        testing::{check_against_reference, with_threads},
+    };
 
     const EXAMPLE_INPUT: &str = include_str!("../inputs/10.ex");
 
     #[test]
     fn test_example() {
-        let (pt1, pt2) = run_on_input(EXAMPLE_INPUT);
-        assert_eq!(7, pt1);
-        assert_eq!(33, pt2);
+        let machines = Day10.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(7, part1(&machines).unwrap());
+        assert_eq!(33, part2(&machines).unwrap());
+    }
+
+    /// A generated input, with enough machines for them to be split between threads
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let machines = Day10.parse(&generate(&mut Rng::new(10), 16)).unwrap();
+        assert_eq!(
+            part2(&machines).unwrap(),
+            with_threads(|| part2_parallel(&machines)).unwrap()
+        );
     }
 
     #[test]
@@ -332,4 +1350,91 @@ mod tests {
         // I don't actually know what the answer should be, we are just testing that it doesn't panic
         let _pt2 = machine.solve_pt2();
     }
+
+    #[test]
+    fn test_matches_naive() {
+        check_against_reference(&Day10, &Day10Naive, 300, |rng| random_machines(rng, 1));
+    }
+
+    #[test]
+    fn test_linear_example() {
+        let machines = Day10Linear.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(
+            33,
+            sum_per_machine(&machines, Machine::fewest_presses_linear).unwrap()
+        );
+    }
+
+    /// A machine from a generated input, where cutting off presses only by how many times each
+    /// button can be pressed gives up after `LINEAR_STEP_LIMIT` combinations, but the relaxed bound
+    /// finds the answer
+    #[test]
+    fn test_linear_relaxed_bound() {
+        let machine: Machine =
+            "[#...#] (1) (2,4) (0,1,3) (0,1,3,4) (3) (2,3,4) (0,3) (0,1) (0,4) (1,2,4) (4) \
+            (0,1,3,4) (0,2,4) {97,63,59,88,112}"
+                .parse()
+                .unwrap();
+        assert_eq!(Some(122), machine.fewest_presses_linear().unwrap());
+        assert!(machine.search_linear(false).is_err());
+    }
+
+    #[test]
+    fn test_linear_matches_naive_with_increments() {
+        check_against_reference(&Day10Linear, &Day10Naive, 300, |rng| {
+            random_machines(rng, 3)
+        });
+    }
+
+    #[test]
+    fn test_linear_matches_default() {
+        check_against_reference(&Day10Linear, &Day10, 100, |rng| {
+            generate_machine(rng, MAX_ARRAY_LEN, 13, 20, 1)
+        });
+    }
+
+    /// A few machines with small joltages, so that the naive solver can search every joltage
+    fn random_machines(rng: &mut Rng, max_increment: usize) -> String {
+        let machine_count = rng.range(1..=3);
+        (0..machine_count)
+            .map(|_| generate_machine(rng, 6, 6, 4, max_increment))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = "[.#x] (0,3) (1,1) {1,2}\n[...........] (0) {1,1,1,1,1,1,1,1,1,1,1}\n[#] {1}\n\
+            [.#] (0,0,0,0,0,0,0,0,0,0,1) {3,7}\n";
+        let violations: Vec<String> = validate(input)
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 1, column 4: unexpected 'x' in indicator lights",
+                "line 1, column 10: light 3 doesn't exist, there are 3 lights",
+                "line 1, column 19: 2 joltages, but there are 3 lights",
+                "line 2, column 2: 11 indicator lights, but at most 10 are supported",
+                "line 3: machine has no buttons",
+                "line 4, column 6: button lists 11 lights, but at most 10 are supported",
+            ],
+            violations
+        );
+    }
+
+    /// Buttons may list a light more than once, so the validator should accept exactly the
+    /// buttons that parse
+    #[test]
+    fn test_validate_repeated_lights() {
+        for input in [
+            "[.#] (0,0,0,0,0,0,0,0,0,1) {3,7}",
+            "[.#] (0,0,0,0,0,0,0,0,0,0,1) {3,7}",
+        ] {
+            assert_eq!(
+                input.parse::<Machine>().is_ok(),
+                validate(input).is_empty(),
+                "{input}"
+            );
+        }
+    }
 }
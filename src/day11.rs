@@ -1,95 +1,431 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::Aoc;
-
-const INPUT: &str = include_str!("../inputs/11.in");
+use crate::{
+    Answer, Aoc, Solution,
+    error::AocError,
+    generate::Rng,
+    validate::{self, Violation},
+};
 
 pub struct Day11;
 
-impl Aoc for Day11 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+/// Counts paths by following every one of them, without assuming that the devices are acyclic.
+/// This is only practical for small inputs, and is used to check [`Day11`].
+pub struct Day11Naive;
+
+pub const ALTERNATIVES: &[(&str, &(dyn Aoc + Send + Sync))] = &[("naive", &Day11Naive)];
+
+/// How many steps the naive solver takes along paths before giving up
+const NAIVE_STEP_LIMIT: usize = 100_000_000;
+
+/// Map from each device to the devices its outputs are connected to
+type Devices = HashMap<String, Vec<String>>;
+
+impl Solution for Day11 {
+    type Parsed = Devices;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        input
+            .trim_end()
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let (device, rest) = line.split_once(": ").ok_or_else(|| {
+                    AocError::parse(i + 1, line, "expected a device name followed by ': '")
+                })?;
+                let outputs = rest.split_whitespace().map(str::to_string).collect();
+                Ok((device.to_string(), outputs))
+            })
+            .collect()
+    }
+
+    fn part1(&self, devices: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(devices)?.into())
+    }
+
+    fn part2(&self, devices: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part2(devices)?.into())
     }
 }
 
-fn run_on_input(input: &str) -> (u64, u64) {
-    let devices: HashMap<&str, Vec<&str>> = input
-        .trim()
-        .lines()
-        .map(|line| {
-            let (device, rest) = line.split_once(": ").unwrap();
-            let outputs = rest.split_whitespace().collect();
-            (device, outputs)
-        })
-        .collect();
+impl Solution for Day11Naive {
+    type Parsed = Devices;
 
-    let pt1 = count_paths("you", "out", &devices);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        Day11.parse(input)
+    }
+
+    fn part1(&self, devices: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(walk_paths("you", "out", &[], devices)?.into())
+    }
+
+    fn part2(&self, devices: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(walk_paths("svr", "out", &["dac", "fft"], devices)?.into())
+    }
+}
+
+fn part1(devices: &Devices) -> Result<u64, AocError> {
+    count_paths("you", "out", devices)
+}
 
+fn part2(devices: &Devices) -> Result<u64, AocError> {
     // If we assume there are no cycles, we can only go from either fft -> dac OR dac --> fft.
     // Figure out which one, and then go from svr --> xxx --> xxx --> out.
-    let dac_to_fft_paths = count_paths("dac", "fft", &devices);
-    let fft_to_dac_paths = count_paths("fft", "dac", &devices);
-    let pt2 = if dac_to_fft_paths == 0 {
-        count_paths("svr", "fft", &devices) * fft_to_dac_paths * count_paths("dac", "out", &devices)
+    let dac_to_fft_paths = count_paths("dac", "fft", devices)?;
+    let fft_to_dac_paths = count_paths("fft", "dac", devices)?;
+    if dac_to_fft_paths == 0 {
+        Ok(count_paths("svr", "fft", devices)?
+            * fft_to_dac_paths
+            * count_paths("dac", "out", devices)?)
     } else if fft_to_dac_paths == 0 {
-        count_paths("svr", "dac", &devices) * dac_to_fft_paths * count_paths("fft", "out", &devices)
+        Ok(count_paths("svr", "dac", devices)?
+            * dac_to_fft_paths
+            * count_paths("fft", "out", devices)?)
     } else {
-        panic!("Either dac --> fft or fft --> dac must be zero to avoid cycles")
-    };
-
-    (pt1, pt2)
+        Err(AocError::unsolvable(
+            "either dac --> fft or fft --> dac must be zero to avoid cycles",
+        ))
+    }
 }
 
-fn count_paths(from: &str, to: &str, edges: &HashMap<&str, Vec<&str>>) -> u64 {
+/// Count the paths from `from` to `to`, failing if a device on the way leads back to itself
+fn count_paths(from: &str, to: &str, edges: &Devices) -> Result<u64, AocError> {
     let mut memo = HashMap::new();
-    count_paths_memo(from, to, edges, &mut memo)
+    let mut in_progress = HashSet::new();
+    count_paths_memo(from, to, edges, &mut memo, &mut in_progress)
 }
 
 fn count_paths_memo<'a>(
     from: &'a str,
-    to: &'a str,
-    edges: &HashMap<&'a str, Vec<&'a str>>,
+    to: &str,
+    edges: &'a Devices,
     memo: &mut HashMap<&'a str, u64>,
-) -> u64 {
-    match memo.get(from) {
-        Some(n) => *n,
-        None => match edges.get(from) {
-            None => 0,
-            Some(outputs) => {
-                let n = outputs
-                    .iter()
-                    .map(|output| {
-                        if output == &to {
-                            1
-                        } else {
-                            count_paths_memo(output, to, edges, memo)
-                        }
-                    })
-                    .sum();
-                memo.insert(from, n);
-                n
+    in_progress: &mut HashSet<&'a str>,
+) -> Result<u64, AocError> {
+    if let Some(n) = memo.get(from) {
+        return Ok(*n);
+    }
+    let Some(outputs) = edges.get(from) else {
+        return Ok(0);
+    };
+    // Paths through a device that's still being counted would go round a loop forever
+    if !in_progress.insert(from) {
+        return Err(AocError::unsolvable(format!(
+            "device {from} leads back to itself, so there are infinitely many paths"
+        )));
+    }
+    let mut n = 0;
+    for output in outputs {
+        n += if output == to {
+            1
+        } else {
+            count_paths_memo(output, to, edges, memo, in_progress)?
+        };
+    }
+    in_progress.remove(from);
+    memo.insert(from, n);
+    Ok(n)
+}
+
+/// Count the paths from `from` to `to` that pass through every device in `via` by walking each
+/// one, never visiting a device twice on the same path
+fn walk_paths(from: &str, to: &str, via: &[&str], devices: &Devices) -> Result<u64, AocError> {
+    let mut path = vec![from];
+    let mut steps = 0;
+    walk_paths_from(&mut path, to, via, devices, &mut steps)
+}
+
+fn walk_paths_from<'a>(
+    path: &mut Vec<&'a str>,
+    to: &str,
+    via: &[&str],
+    devices: &'a Devices,
+    steps: &mut usize,
+) -> Result<u64, AocError> {
+    *steps += 1;
+    if *steps > NAIVE_STEP_LIMIT {
+        return Err(AocError::unsolvable("too many paths for the naive solver"));
+    }
+
+    let current = path[path.len() - 1];
+    if current == to {
+        return Ok(via.iter().all(|device| path.contains(device)).into());
+    }
+
+    let mut count = 0;
+    for output in devices.get(current).into_iter().flatten() {
+        if !path.contains(&output.as_str()) {
+            path.push(output);
+            count += walk_paths_from(path, to, via, devices, steps)?;
+            path.pop();
+        }
+    }
+    Ok(count)
+}
+
+/// Check that every line is a device like `aaa: you hhh`, that each device is listed once, that
+/// every output is a listed device or `out`, and that no path loops back on itself, since paths
+/// are counted by recursing from each device
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let mut devices: HashMap<&str, (usize, Vec<&str>)> = HashMap::new();
+    let mut order = Vec::new();
+    for (n, line) in validate::lines(input) {
+        let Some((device, outputs)) = line.split_once(": ") else {
+            violations.push(Violation::line(
+                n,
+                "expected a device name followed by ': '",
+            ));
+            continue;
+        };
+        let outputs: Vec<&str> = outputs.split_whitespace().collect();
+        for name in std::iter::once(device).chain(outputs.iter().copied()) {
+            if name.is_empty() || !name.bytes().all(|b| b.is_ascii_lowercase()) {
+                violations.push(Violation::at(
+                    n,
+                    validate::column(line, name),
+                    format!("device name {name:?} should be lowercase letters"),
+                ));
+            }
+        }
+        if device == "out" {
+            violations.push(Violation::line(n, "'out' shouldn't have any outputs"));
+        }
+        match devices.get(device) {
+            Some((first, _)) => violations.push(Violation::line(
+                n,
+                format!("device {device} is already listed on line {first}"),
+            )),
+            None => {
+                devices.insert(device, (n, outputs));
+                order.push(device);
             }
-        },
+        }
     }
+
+    for (n, outputs) in devices.values() {
+        let line = input.lines().nth(n - 1).unwrap_or_default();
+        for output in outputs {
+            if *output != "out" && !devices.contains_key(output) {
+                violations.push(Violation::at(
+                    *n,
+                    validate::column(line, output),
+                    format!("output {output} isn't a listed device"),
+                ));
+            }
+        }
+    }
+
+    // Depth first search, reporting the first device found on each cycle
+    #[derive(Clone, Copy, PartialEq)]
+    enum Visit {
+        InProgress,
+        Done,
+    }
+    fn visit<'a>(
+        device: &'a str,
+        devices: &HashMap<&'a str, (usize, Vec<&'a str>)>,
+        visits: &mut HashMap<&'a str, Visit>,
+        violations: &mut Vec<Violation>,
+    ) {
+        visits.insert(device, Visit::InProgress);
+        let (n, outputs) = &devices[device];
+        for &output in outputs {
+            if !devices.contains_key(output) {
+                continue;
+            }
+            match visits.get(output) {
+                None => visit(output, devices, visits, violations),
+                Some(Visit::InProgress) => violations.push(Violation::line(
+                    *n,
+                    format!("output {output} leads back to {device}, making a loop"),
+                )),
+                Some(Visit::Done) => {}
+            }
+        }
+        visits.insert(device, Visit::Done);
+    }
+    let mut visits = HashMap::new();
+    for device in order {
+        if !visits.contains_key(device) {
+            visit(device, &devices, &mut visits, &mut violations);
+        }
+    }
+
+    violations.sort_by_key(|violation| (violation.line, violation.column));
+    violations
+}
+
+/// About `size` devices in layers, where each device's outputs go to devices in the next layer so
+/// that there are no cycles. `svr` is alone in the first layer and `out` in the last, and `dac`
+/// and `fft` are each alone in a layer (in a random order) so that every path from `svr` goes
+/// through both of them. `you` is somewhere in between. There are always 20 layers however large
+/// the input, so that the number of paths stays well within a `u64`.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const LAYERS: usize = 20;
+    const NAMED: [&str; 5] = ["svr", "you", "dac", "fft", "out"];
+
+    let width = size.div_ceil(LAYERS);
+    let mut names = (0..)
+        .map(device_name)
+        .filter(|name| !NAMED.contains(&name.as_str()));
+
+    let mut middle_layers: Vec<usize> = (1..=LAYERS).collect();
+    rng.shuffle(&mut middle_layers);
+    let (dac_layer, fft_layer, you_layer) = (middle_layers[0], middle_layers[1], middle_layers[2]);
+
+    let mut layers = vec![vec!["svr".to_string()]];
+    for layer in 1..=LAYERS {
+        if layer == dac_layer {
+            layers.push(vec!["dac".to_string()]);
+        } else if layer == fft_layer {
+            layers.push(vec!["fft".to_string()]);
+        } else {
+            layers.push(names.by_ref().take(width).collect());
+        }
+    }
+    layers.push(vec!["out".to_string()]);
+    let you = rng.range(0..=width - 1);
+    layers[you_layer][you] = "you".to_string();
+
+    let mut input = String::new();
+    for pair in layers.windows(2) {
+        let (from, to) = (&pair[0], &pair[1]);
+
+        // Every device has at least one input and one output, so every device is on a path from
+        // `svr` to `out`
+        let mut outputs = vec![Vec::new(); from.len()];
+        for i in 0..to.len() {
+            outputs[rng.range(0..=from.len() - 1)].push(i);
+        }
+        for device_outputs in &mut outputs {
+            if device_outputs.is_empty() || rng.chance(1, 2) {
+                device_outputs.push(rng.range(0..=to.len() - 1));
+            }
+            device_outputs.sort_unstable();
+            device_outputs.dedup();
+        }
+
+        for (name, device_outputs) in from.iter().zip(outputs) {
+            let device_outputs: Vec<&str> =
+                device_outputs.iter().map(|&i| to[i].as_str()).collect();
+            input += &format!("{name}: {}\n", device_outputs.join(" "));
+        }
+    }
+    input
+}
+
+/// A device name of three letters, or more if there are over 17576 devices
+fn device_name(i: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = i;
+    while name.len() < 3 || n > 0 {
+        name.push(b'a' + (n % 26) as u8);
+        n /= 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{error::ErrorKind, generate::Rng, testing::check_against_reference};
 
     const EXAMPLE_INPUT: &str = include_str!("../inputs/11.ex");
     const EXAMPLE_INPUT_2: &str = include_str!("../inputs/11.2.ex");
 
     #[test]
     fn test_example() {
-        let (pt1, _) = run_on_input(EXAMPLE_INPUT);
-        assert_eq!(5, pt1);
+        let devices = Day11.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(5, part1(&devices).unwrap());
     }
 
     #[test]
     fn test_example_2() {
-        let (_, pt2) = run_on_input(EXAMPLE_INPUT_2);
-        assert_eq!(2, pt2);
+        let devices = Day11.parse(EXAMPLE_INPUT_2).unwrap();
+        assert_eq!(2, part2(&devices).unwrap());
+    }
+
+    #[test]
+    fn test_cycle() {
+        let input = "you: aaa\naaa: bbb\nbbb: aaa out\nsvr: dac\ndac: fft\nfft: out\n";
+        let devices = Day11.parse(input).unwrap();
+        let error = Day11.part1(&devices).unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Unsolvable { .. }));
+        assert_eq!(1, part2(&devices).unwrap());
+    }
+
+    #[test]
+    fn test_matches_naive() {
+        check_against_reference(&Day11, &Day11Naive, 250, |rng| random_devices(rng, false));
+        check_against_reference(&Day11, &Day11Naive, 250, |rng| random_devices(rng, true));
+    }
+
+    /// Random devices with a loop back to `svr` from every device, which can't be counted
+    #[test]
+    fn test_generated_cycle() {
+        let mut rng = Rng::new(11);
+        for fft_first in [false, true] {
+            for _ in 0..25 {
+                let input: String = random_devices(&mut rng, fft_first)
+                    .lines()
+                    .map(|line| format!("{line} svr\n"))
+                    .collect();
+                let devices = Day11.parse(&input).unwrap();
+                let error = Day11.part2(&devices).unwrap_err();
+                assert!(
+                    matches!(error.kind(), ErrorKind::Unsolvable { .. }),
+                    "{input}"
+                );
+            }
+        }
+    }
+
+    /// Random devices with no cycles, as in the puzzle input, with `fft` before `dac` if
+    /// `fft_first` is set and after it otherwise. The other devices are placed in a random order.
+    fn random_devices(rng: &mut Rng, fft_first: bool) -> String {
+        let mut names: Vec<String> = ["you", "svr", "dac", "fft"]
+            .into_iter()
+            .map(str::to_string)
+            .chain((0..rng.range(1..=8)).map(|i| format!("d{i:02}")))
+            .collect();
+        rng.shuffle(&mut names);
+        let dac = names.iter().position(|name| name == "dac").unwrap();
+        let fft = names.iter().position(|name| name == "fft").unwrap();
+        if (fft < dac) != fft_first {
+            names.swap(dac, fft);
+        }
+        names.push("out".to_string());
+
+        // Outputs only go to devices later in the list, so there can't be any cycles
+        let mut input = String::new();
+        for (i, name) in names.iter().enumerate().take(names.len() - 1) {
+            let later = &names[i + 1..];
+            let mut outputs: Vec<&str> = (0..rng.range(1..=3))
+                .map(|_| later[rng.range(0..=later.len() - 1)].as_str())
+                .collect();
+            outputs.sort_unstable();
+            outputs.dedup();
+            input += &format!("{name}: {}\n", outputs.join(" "));
+        }
+        input
+    }
+
+    #[test]
+    fn test_validate() {
+        let violations: Vec<String> = validate("aaa: bbb Ccc\nbbb: ddd out\nddd: aaa\naaa: out\n")
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 1, column 10: device name \"Ccc\" should be lowercase letters",
+                "line 1, column 10: output Ccc isn't a listed device",
+                "line 3: output aaa leads back to ddd, making a loop",
+                "line 4: device aaa is already listed on line 1",
+            ],
+            violations
+        );
     }
 }
@@ -1,90 +1,1424 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+    ops::RangeInclusive,
+    str::FromStr,
+};
 
-use crate::Aoc;
-
-const INPUT: &str = include_str!("../inputs/12.in");
+use crate::{
+    Answer, Aoc, Solution,
+    error::{AocError, parse_lines, parse_num},
+    generate::Rng,
+    trace,
+    validate::{self, Violation},
+    visualize::Visualization,
+};
 
 pub struct Day12;
 
-impl Aoc for Day12 {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+/// Checks whether the presents fit in each region by searching every way of placing them. This is
+/// only practical for small regions, and is used to check [`Day12`].
+pub struct Day12Naive;
+
+/// Only counts the regions that the bounds in [`classify`] settle, and fails if any region would
+/// need a search. This is enough for the puzzle input, and skips making placements.
+pub struct Day12Bounds;
+
+pub const ALTERNATIVES: &[(&str, &(dyn Aoc + Send + Sync))] =
+    &[("naive", &Day12Naive), ("bounds", &Day12Bounds)];
+
+/// How many placements the naive solver tries in one region before giving up
+const NAIVE_STEP_LIMIT: usize = 10_000_000;
+
+/// How many times [`pack`] settles a tile in one region before giving up
+const SEARCH_STEP_LIMIT: usize = 100_000;
+
+/// The presents by index
+type Presents = HashMap<usize, Present>;
+
+impl Solution for Day12 {
+    type Parsed = (Presents, Vec<Region>);
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, (presents, regions): &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part1(presents, regions)?.into())
+    }
+
+    fn part2(&self, _parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(Answer::NotImplemented)
+    }
+}
+
+impl Solution for Day12Bounds {
+    type Parsed = (Presents, Vec<Region>);
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, (presents, regions): &Self::Parsed) -> Result<Answer, AocError> {
+        let counts = TierCounts::new(&classify_all(presents, regions)?);
+        if counts.unknown > 0 {
+            return Err(AocError::unsolvable(format!(
+                "the bounds don't settle every region: {counts}"
+            )));
+        }
+        Ok(counts.fits.into())
+    }
+
+    fn part2(&self, _parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(Answer::NotImplemented)
+    }
+}
+
+impl Solution for Day12Naive {
+    type Parsed = (Presents, Vec<Region>);
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        parse_input(input)
+    }
+
+    fn part1(&self, (presents, regions): &Self::Parsed) -> Result<Answer, AocError> {
+        let mut count: usize = 0;
+        for region in regions {
+            if fits_naive(region, presents)? {
+                count += 1;
+            }
+        }
+        Ok(count.into())
+    }
+
+    fn part2(&self, _parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(Answer::NotImplemented)
     }
 }
 
-fn run_on_input(input: &str) -> (usize, usize) {
-    let (presents, regions) = input.trim().rsplit_once("\n\n").unwrap();
+fn parse_input(input: &str) -> Result<(Presents, Vec<Region>), AocError> {
+    let (presents, regions) = input.trim_end().rsplit_once("\n\n").ok_or_else(|| {
+        AocError::parse(
+            1,
+            input.lines().next().unwrap_or_default(),
+            "expected presents, then a blank line, then regions",
+        )
+    })?;
 
-    let regions: Vec<Region> = regions.lines().map(|line| line.parse().unwrap()).collect();
+    // Skip the presents and the blank line separating them from the regions
+    let regions_offset = presents.lines().count() + 1;
+    let regions: Vec<Region> = parse_lines(regions).map_err(|e| e.offset_lines(regions_offset))?;
 
-    // We don't actually need the presents for anything, all we're really doing here is asserting
-    // some assumptions about the input.
-    let _presents: HashMap<usize, Present> = presents
+    let mut chunk_offset = 0;
+    let presents: Presents = presents
         .split("\n\n")
         .map(|chunk| {
-            let (index, rest) = chunk.split_once(':').unwrap();
-            (index.parse().unwrap(), rest.trim().parse().unwrap())
+            let line = chunk_offset + 1;
+            let first_line = chunk.lines().next().unwrap_or_default();
+            chunk_offset += chunk.lines().count() + 1;
+
+            let (index, rest) = chunk
+                .split_once(':')
+                .ok_or_else(|| AocError::parse(line, first_line, "expected a present index"))?;
+            let index = parse_num(index, "present index")
+                .map_err(|e| AocError::parse(line, first_line, e))?;
+            let present = rest
+                .trim()
+                .parse()
+                .map_err(|e| AocError::parse(line, first_line, e))?;
+            Ok((index, present))
+        })
+        .collect::<Result<_, AocError>>()?;
+
+    Ok((presents, regions))
+}
+
+fn part1(presents: &Presents, regions: &[Region]) -> Result<usize, AocError> {
+    let tiers = classify_all(presents, regions)?;
+    let mut count = tiers.iter().filter(|&&tier| tier == Tier::Fits).count();
+    for (i, (region, tier)) in regions.iter().zip(&tiers).enumerate() {
+        if *tier == Tier::Unknown {
+            let _search = trace::span("search");
+            let shapes = region_presents(region, presents)?;
+            let placement =
+                search(region, &shapes).map_err(|e| e.context(format!("region {}", i + 1)))?;
+            if placement.is_some() {
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// Whether each region's presents fit, as far as the bounds can tell without searching
+fn classify_all(presents: &Presents, regions: &[Region]) -> Result<Vec<Tier>, AocError> {
+    let _classify = trace::span("classify");
+    regions
+        .iter()
+        .enumerate()
+        .map(|(i, region)| {
+            classify(region, presents).map_err(|e| e.context(format!("region {}", i + 1)))
+        })
+        .collect()
+}
+
+/// What the bounds can tell about whether a region's presents fit, without searching
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tier {
+    /// The region can be split into a cell for every present, each as big as the largest
+    /// present's bounding box, so each present can go in its own cell
+    Fits,
+    /// The presents cover more tiles than the region has
+    CannotFit,
+    /// Neither bound settles it, so it takes a search
+    Unknown,
+}
+
+/// How many regions are in each [`Tier`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TierCounts {
+    pub fits: usize,
+    pub cannot_fit: usize,
+    pub unknown: usize,
+}
+
+impl TierCounts {
+    pub fn new(tiers: &[Tier]) -> Self {
+        let mut counts = TierCounts::default();
+        for tier in tiers {
+            match tier {
+                Tier::Fits => counts.fits += 1,
+                Tier::CannotFit => counts.cannot_fit += 1,
+                Tier::Unknown => counts.unknown += 1,
+            }
+        }
+        counts
+    }
+}
+
+impl Display for TierCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} fit, {} can't fit and {} need searching",
+            self.fits, self.cannot_fit, self.unknown
+        )
+    }
+}
+
+fn classify(region: &Region, presents: &Presents) -> Result<Tier, AocError> {
+    let shapes = region_presents(region, presents)?;
+    if present_area(region, presents) > region.width * region.height {
+        return Ok(Tier::CannotFit);
+    }
+    let (cell_width, cell_height) = cell_size(region, &shapes);
+    let cells = (region.width / cell_width) * (region.height / cell_height);
+    let total_count: usize = region.counts.iter().sum();
+    if total_count <= cells {
+        Ok(Tier::Fits)
+    } else {
+        Ok(Tier::Unknown)
+    }
+}
+
+/// The presents the region needs, by index
+fn region_presents<'a>(
+    region: &Region,
+    presents: &'a Presents,
+) -> Result<Vec<&'a Present>, AocError> {
+    (0..region.counts.len())
+        .map(|i| {
+            presents.get(&i).ok_or_else(|| {
+                AocError::unsolvable(format!("region needs present {i}, which doesn't exist"))
+            })
+        })
+        .collect()
+}
+
+/// The size of a cell that any of the region's presents fits in
+fn cell_size(region: &Region, shapes: &[&Present]) -> (usize, usize) {
+    region
+        .counts
+        .iter()
+        .zip(shapes)
+        .filter(|&(&count, _)| count > 0)
+        .map(|(_, present)| present.bounding_box())
+        .fold((1, 1), |(w, h), (width, height)| {
+            (w.max(width), h.max(height))
+        })
+}
+
+/// A present placed in a region
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlacedPresent {
+    /// The index of the present
+    pub present: usize,
+    /// The (x, y) of each tile the present covers
+    pub tiles: Vec<(usize, usize)>,
+}
+
+/// Where each present goes in a region that they all fit in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    pub width: usize,
+    pub height: usize,
+    pub presents: Vec<PlacedPresent>,
+}
+
+/// Find a way to fit all of the presents for the region into it, or None if there isn't one, using
+/// the bounds from [`classify`] to avoid searching where possible
+fn pack(region: &Region, presents: &Presents) -> Result<Option<Placement>, AocError> {
+    let shapes = region_presents(region, presents)?;
+    match classify(region, presents)? {
+        Tier::Fits => Ok(Some(one_per_cell(region, &shapes))),
+        Tier::CannotFit => Ok(None),
+        Tier::Unknown => search(region, &shapes),
+    }
+}
+
+/// Place each present in its own cell, for a region in [`Tier::Fits`]
+fn one_per_cell(region: &Region, shapes: &[&Present]) -> Placement {
+    let (cell_width, cell_height) = cell_size(region, shapes);
+    let columns = region.width / cell_width;
+    let presents = region
+        .counts
+        .iter()
+        .enumerate()
+        .flat_map(|(present, &count)| std::iter::repeat_n(present, count))
+        .enumerate()
+        .map(|(cell, present)| {
+            let (left, top) = (
+                cell_width * (cell % columns),
+                cell_height * (cell / columns),
+            );
+            let tiles = shapes[present]
+                .tiles
+                .iter()
+                .map(|&(x, y)| (left + x as usize, top + y as usize))
+                .collect();
+            PlacedPresent { present, tiles }
+        })
+        .collect();
+    Placement {
+        width: region.width,
+        height: region.height,
+        presents,
+    }
+}
+
+/// Search for a way to fit the presents into the region, or None if there isn't one. This is an
+/// exact cover problem: every present must be placed exactly once, and every tile either covered
+/// once or left empty, where only as many tiles can be left empty as the presents don't need. It's
+/// solved by depth first search, settling whichever tile has the fewest placements left that could
+/// cover it at each step.
+fn search(region: &Region, shapes: &[&Present]) -> Result<Option<Placement>, AocError> {
+    let area = region.width * region.height;
+    // Presents with the same shape are interchangeable, so they're searched for together. Each
+    // group lists a copy of a present's index for every time that present has to be placed.
+    let mut groups: Vec<(Shape, Vec<usize>)> = Vec::new();
+    for (present, &count) in region.counts.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        let canonical = shapes[present].canonical();
+        let copies = std::iter::repeat_n(present, count);
+        match groups.iter_mut().find(|(shape, _)| *shape == canonical) {
+            Some((_, group)) => group.extend(copies),
+            None => groups.push((canonical, copies.collect())),
+        }
+    }
+    let needed: usize = groups
+        .iter()
+        .map(|(shape, group)| shape.len() * group.len())
+        .sum();
+    if needed > area {
+        return Ok(None);
+    }
+
+    let places = Places::new(
+        groups
+            .iter()
+            .map(|(_, group)| candidates(shapes[group[0]], region.width, region.height))
+            .collect(),
+        area,
+    );
+    let mut search = Search::new(
+        &places,
+        groups.iter().map(|(_, group)| group.len()).collect(),
+        area - needed,
+    );
+    if !search.search()? {
+        return Ok(None);
+    }
+
+    let mut copies: Vec<_> = groups
+        .into_iter()
+        .map(|(_, group)| group.into_iter())
+        .collect();
+    let presents = search
+        .placed
+        .iter()
+        .map(|&place| PlacedPresent {
+            present: copies[places.shapes[place]].next().unwrap(),
+            tiles: places.tiles[place]
+                .iter()
+                .map(|&i| (i % region.width, i / region.width))
+                .collect(),
         })
         .collect();
+    Ok(Some(Placement {
+        width: region.width,
+        height: region.height,
+        presents,
+    }))
+}
 
-    let pt1 = regions
+/// How many tiles the region's presents cover between them, ignoring any that don't exist
+fn present_area(region: &Region, presents: &Presents) -> usize {
+    region
+        .counts
         .iter()
-        .filter(|region| {
-            // If each present is in its own 3x3 square, and there are less presents than 3x3
-            // squares in the region, then they definitely all fit. Note that this is a lower bound,
-            // it's possible to have regions that fit all the presents but do not have enough 3x3
-            // squares, but in the case of the actual input this case does not seem to happen.
-            let num_squares_in_region = (region.width / 3) * (region.height / 3);
-            let total_count: usize = region.counts.iter().copied().sum();
-            total_count <= num_squares_in_region
+        .enumerate()
+        .map(|(i, count)| count * presents.get(&i).map_or(0, Present::area))
+        .sum()
+}
+
+/// The tiles covered by every position and orientation of the present that lies inside the region,
+/// as indexes into the region's tiles
+fn candidates(present: &Present, width: usize, height: usize) -> Vec<Vec<usize>> {
+    let mut candidates = Vec::new();
+    for shape in present.orientations() {
+        let min_x = shape.iter().map(|&(x, _)| x).min().unwrap_or(0);
+        let max_x = shape.iter().map(|&(x, _)| x).max().unwrap_or(0);
+        let max_y = shape.iter().map(|&(_, y)| y).max().unwrap_or(0);
+        // Shapes start at their first tile, which is on their top row but not always leftmost
+        for y in 0..height as i64 - max_y {
+            for x in -min_x..width as i64 - max_x {
+                candidates.push(
+                    shape
+                        .iter()
+                        .map(|&(dx, dy)| ((y + dy) * width as i64 + x + dx) as usize)
+                        .collect(),
+                );
+            }
+        }
+    }
+    candidates
+}
+
+/// Every place a present could go in a region, from [`candidates`], indexed for [`Search`]
+struct Places {
+    /// The shape of each place
+    shapes: Vec<usize>,
+    /// The tiles each place covers
+    tiles: Vec<Vec<usize>>,
+    /// The places covering each tile
+    covering: Vec<Vec<usize>>,
+    /// The places of each shape
+    by_shape: Vec<Vec<usize>>,
+}
+
+impl Places {
+    fn new(candidates: Vec<Vec<Vec<usize>>>, area: usize) -> Self {
+        let mut places = Places {
+            shapes: Vec::new(),
+            tiles: Vec::new(),
+            covering: vec![Vec::new(); area],
+            by_shape: vec![Vec::new(); candidates.len()],
+        };
+        for (shape, candidates) in candidates.into_iter().enumerate() {
+            for tiles in candidates {
+                let place = places.tiles.len();
+                for &i in &tiles {
+                    places.covering[i].push(place);
+                }
+                places.by_shape[shape].push(place);
+                places.shapes.push(shape);
+                places.tiles.push(tiles);
+            }
+        }
+        places
+    }
+}
+
+/// The state of the exact cover search for [`pack`]. The counts that guide it are kept up to date
+/// as presents are placed and taken away again, rather than counted afresh at every step.
+struct Search<'a> {
+    places: &'a Places,
+    /// Whether each tile is settled, either covered by a present or left empty
+    settled: Vec<bool>,
+    /// How many reasons each place can't be used: its tiles that are settled, plus one if every
+    /// present of its shape has been placed
+    blocked: Vec<usize>,
+    /// How many usable places cover each tile
+    options: Vec<usize>,
+    /// How many usable places each shape has
+    available: Vec<usize>,
+    /// How many presents of each shape are still to be placed
+    remaining: Vec<usize>,
+    /// How many more tiles can be left empty
+    spare: usize,
+    /// The places used so far
+    placed: Vec<usize>,
+    steps: usize,
+}
+
+impl<'a> Search<'a> {
+    fn new(places: &'a Places, remaining: Vec<usize>, spare: usize) -> Self {
+        Search {
+            places,
+            settled: vec![false; places.covering.len()],
+            blocked: vec![0; places.tiles.len()],
+            options: places.covering.iter().map(Vec::len).collect(),
+            available: places.by_shape.iter().map(Vec::len).collect(),
+            remaining,
+            spare,
+            placed: Vec::new(),
+            steps: 0,
+        }
+    }
+
+    fn search(&mut self) -> Result<bool, AocError> {
+        self.steps += 1;
+        if self.steps > SEARCH_STEP_LIMIT {
+            return Err(AocError::unsolvable(
+                "gave up searching for a way to pack the region",
+            ));
+        }
+
+        if self.remaining.iter().all(|&count| count == 0) {
+            return Ok(true);
+        }
+        if self
+            .remaining
+            .iter()
+            .zip(&self.available)
+            .any(|(remaining, available)| available < remaining)
+        {
+            return Ok(false);
+        }
+        // Tiles with no way left to cover them have to be left empty, and otherwise the tile with
+        // the fewest ways is the one to settle next
+        let mut tile = None;
+        let mut uncoverable = 0;
+        for i in (0..self.settled.len()).filter(|&i| !self.settled[i]) {
+            if self.options[i] == 0 {
+                uncoverable += 1;
+            }
+            if tile.is_none_or(|tile| self.options[i] < self.options[tile]) {
+                tile = Some(i);
+            }
+        }
+        let Some(tile) = tile else {
+            return Ok(false);
+        };
+        if uncoverable > self.spare {
+            return Ok(false);
+        }
+
+        let places = self.places;
+        for &place in &places.covering[tile] {
+            if self.blocked[place] > 0 {
+                continue;
+            }
+            self.place(place, true);
+            if self.search()? {
+                return Ok(true);
+            }
+            self.place(place, false);
+        }
+
+        if self.spare > 0 {
+            self.spare -= 1;
+            self.settle(tile, true);
+            if self.search()? {
+                return Ok(true);
+            }
+            self.settle(tile, false);
+            self.spare += 1;
+        }
+        Ok(false)
+    }
+
+    /// Put a present in the place, or take it away again
+    fn place(&mut self, place: usize, placed: bool) {
+        let places = self.places;
+        let shape = places.shapes[place];
+        for &i in &places.tiles[place] {
+            self.settle(i, placed);
+        }
+        if placed {
+            self.remaining[shape] -= 1;
+            self.placed.push(place);
+        }
+        if self.remaining[shape] == 0 {
+            for &other in &places.by_shape[shape] {
+                self.block(other, placed);
+            }
+        }
+        if !placed {
+            self.remaining[shape] += 1;
+            self.placed.pop();
+        }
+    }
+
+    /// Mark the tile as settled, or not, along with every place covering it
+    fn settle(&mut self, tile: usize, settled: bool) {
+        self.settled[tile] = settled;
+        for &place in &self.places.covering[tile] {
+            self.block(place, settled);
+        }
+    }
+
+    /// Add or remove a reason the place can't be used, updating the counts if that changes
+    /// whether it can
+    fn block(&mut self, place: usize, blocked: bool) {
+        if !blocked {
+            self.blocked[place] -= 1;
+        }
+        if self.blocked[place] == 0 {
+            let places = self.places;
+            for &i in &places.tiles[place] {
+                if blocked {
+                    self.options[i] -= 1;
+                } else {
+                    self.options[i] += 1;
+                }
+            }
+            if blocked {
+                self.available[places.shapes[place]] -= 1;
+            } else {
+                self.available[places.shapes[place]] += 1;
+            }
+        }
+        if blocked {
+            self.blocked[place] += 1;
+        }
+    }
+}
+
+/// Whether all of the presents for the region fit in it, by trying every position and orientation
+/// of each present
+fn fits_naive(region: &Region, presents: &Presents) -> Result<bool, AocError> {
+    let orientations = (0..region.counts.len())
+        .map(|i| {
+            presents.get(&i).map(Present::orientations).ok_or_else(|| {
+                AocError::unsolvable(format!("region needs present {i}, which doesn't exist"))
+            })
         })
-        .count();
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let area = region.width * region.height;
+    let present_area: usize = region
+        .counts
+        .iter()
+        .zip(&orientations)
+        .map(|(count, orientations)| count * orientations[0].len())
+        .sum();
+    if present_area > area {
+        return Ok(false);
+    }
 
-    (pt1, 0)
+    let mut packing = Packing {
+        width: region.width,
+        height: region.height,
+        filled: vec![false; area],
+        remaining: region.counts.clone(),
+        orientations: &orientations,
+        spare: area - present_area,
+        steps: 0,
+    };
+    packing.pack(0)
 }
 
+/// The state of the search for a way to fit the presents into a region
+struct Packing<'a> {
+    width: usize,
+    height: usize,
+    filled: Vec<bool>,
+    /// How many of each present are still to be placed
+    remaining: Vec<usize>,
+    /// Every orientation of each present
+    orientations: &'a [Vec<Shape>],
+    /// How many more tiles can be left empty
+    spare: usize,
+    steps: usize,
+}
+
+impl Packing<'_> {
+    /// Fill the first empty tile from `from` onwards, either with a present or by leaving it
+    /// empty, and carry on until every present is placed
+    fn pack(&mut self, from: usize) -> Result<bool, AocError> {
+        self.steps += 1;
+        if self.steps > NAIVE_STEP_LIMIT {
+            return Err(AocError::unsolvable(
+                "region too large for the naive solver",
+            ));
+        }
+
+        if self.remaining.iter().all(|&count| count == 0) {
+            return Ok(true);
+        }
+        let Some(tile) = (from..self.filled.len()).find(|&i| !self.filled[i]) else {
+            return Ok(false);
+        };
+        let (x, y) = ((tile % self.width) as i64, (tile / self.width) as i64);
+
+        for present in 0..self.remaining.len() {
+            if self.remaining[present] == 0 {
+                continue;
+            }
+            for shape in self.orientations[present].iter() {
+                // Each shape starts at its first tile, so the shape is placed with that tile here
+                let tiles: Option<Vec<usize>> = shape
+                    .iter()
+                    .map(|&(dx, dy)| {
+                        let (x, y) = (x + dx, y + dy);
+                        let in_region = (0..self.width as i64).contains(&x)
+                            && (0..self.height as i64).contains(&y);
+                        let i = (y * self.width as i64 + x) as usize;
+                        (in_region && !self.filled[i]).then_some(i)
+                    })
+                    .collect();
+                let Some(tiles) = tiles else {
+                    continue;
+                };
+
+                self.set(&tiles, true);
+                self.remaining[present] -= 1;
+                let fits = self.pack(tile + 1)?;
+                self.remaining[present] += 1;
+                self.set(&tiles, false);
+                if fits {
+                    return Ok(true);
+                }
+            }
+        }
+
+        if self.spare > 0 {
+            self.spare -= 1;
+            self.filled[tile] = true;
+            let fits = self.pack(tile + 1)?;
+            self.filled[tile] = false;
+            self.spare += 1;
+            return Ok(fits);
+        }
+        Ok(false)
+    }
+
+    fn set(&mut self, tiles: &[usize], filled: bool) {
+        for &i in tiles {
+            self.filled[i] = filled;
+        }
+    }
+}
+
+/// The tiles of a present relative to its first tile, in reading order
+type Shape = Vec<(i64, i64)>;
+
 #[derive(Debug, Clone)]
-struct Present;
+pub struct Present {
+    /// The (x, y) of each tile of the present
+    tiles: Vec<(i64, i64)>,
+}
+
+impl Present {
+    /// How many tiles the present covers
+    fn area(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// The width and height of the smallest rectangle that the present fits in, as it's given
+    fn bounding_box(&self) -> (usize, usize) {
+        let width = self.tiles.iter().map(|&(x, _)| x).max().unwrap_or(-1) + 1;
+        let height = self.tiles.iter().map(|&(_, y)| y).max().unwrap_or(-1) + 1;
+        (width as usize, height as usize)
+    }
+
+    /// The orientation that sorts first, with its bounding box at the origin. Two presents have
+    /// the same canonical orientation exactly when one can be rotated or flipped into the other.
+    fn canonical(&self) -> Shape {
+        self.orientations()
+            .into_iter()
+            .map(|shape| {
+                let min_x = shape.iter().map(|&(x, _)| x).min().unwrap_or(0);
+                shape.into_iter().map(|(x, y)| (x - min_x, y)).collect()
+            })
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// Every distinct way the present can be rotated and flipped
+    fn orientations(&self) -> Vec<Shape> {
+        let mut shapes: Vec<Shape> = Vec::new();
+        let mut tiles = self.tiles.clone();
+        for _ in 0..4 {
+            // Rotate a quarter turn
+            tiles = tiles.iter().map(|&(x, y)| (-y, x)).collect();
+            let flipped = tiles.iter().map(|&(x, y)| (-x, y)).collect();
+            for mut shape in [tiles.clone(), flipped] {
+                shape.sort_unstable_by_key(|&(x, y)| (y, x));
+                let (first_x, first_y) = shape[0];
+                for tile in &mut shape {
+                    *tile = (tile.0 - first_x, tile.1 - first_y);
+                }
+                if !shapes.contains(&shape) {
+                    shapes.push(shape);
+                }
+            }
+        }
+        shapes
+    }
+}
 
 impl FromStr for Present {
-    type Err = ();
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (width, height) = s.trim().lines().fold((0, 0), |(w, h), line| {
-            let width = line.len();
-            (w.max(width), h + 1)
-        });
+        let mut tiles = Vec::new();
+        for (y, line) in s.trim().lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '#' => tiles.push((x as i64, y as i64)),
+                    '.' => {}
+                    _ => return Err(format!("unexpected {c:?} in present")),
+                }
+            }
+        }
+        if tiles.is_empty() {
+            return Err("present has no tiles".to_string());
+        }
 
-        // Based on inspection of the input, everything is 3x3.
-        assert_eq!(3, width);
-        assert_eq!(3, height);
+        // Presents can be any shape, so move the tiles to the top left corner in case the first
+        // rows or columns are empty
+        let min_x = tiles.iter().map(|&(x, _)| x).min().unwrap_or(0);
+        let min_y = tiles.iter().map(|&(_, y)| y).min().unwrap_or(0);
+        for tile in &mut tiles {
+            *tile = (tile.0 - min_x, tile.1 - min_y);
+        }
 
-        Ok(Present)
+        Ok(Present { tiles })
     }
 }
 
 #[derive(Debug, Clone)]
-struct Region {
+pub struct Region {
     width: usize,
     height: usize,
     counts: Vec<usize>,
 }
 
 impl FromStr for Region {
-    type Err = ();
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (width, rest) = s.split_once('x').unwrap();
-        let (height, rest) = rest.split_once(": ").unwrap();
+        let (size, rest) = s
+            .split_once(": ")
+            .ok_or("expected a region like 12x5: 1 0 1 0 2 2")?;
+        let (width, height) = size.split_once('x').ok_or("expected a size like 12x5")?;
         let counts = rest
             .split_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect();
+            .map(|n| parse_num(n, "present count"))
+            .collect::<Result<_, _>>()?;
         Ok(Region {
-            width: width.parse().unwrap(),
-            height: height.parse().unwrap(),
+            width: parse_num(width, "width")?,
+            height: parse_num(height, "height")?,
             counts,
         })
     }
 }
+
+/// The size of a tile in the SVG drawing
+const SVG_TILE: usize = 16;
+const SVG_MARGIN: usize = 10;
+const SVG_CAPTION_HEIGHT: usize = 24;
+
+/// Draw each region with a border around every present, labelled with the present's index, or
+/// crossed out with the reason it can't be packed, followed by how many regions are in each tier
+pub fn visualize(input: &str) -> Result<Visualization, AocError> {
+    let (presents, regions) = parse_input(input)?;
+    let tiers = classify_all(&presents, &regions)?;
+
+    let width = regions.iter().map(|region| region.width).max().unwrap_or(0);
+    let svg_width = (width * SVG_TILE).max(400) + 2 * SVG_MARGIN;
+    let mut text = String::new();
+    let mut svg = String::new();
+    let mut y = SVG_MARGIN;
+    for (i, (region, tier)) in regions.iter().zip(&tiers).enumerate() {
+        let (caption, owners, labels) = match pack(region, &presents) {
+            Ok(Some(placement)) => {
+                let mut owners = vec![None; region.width * region.height];
+                for (n, placed) in placement.presents.iter().enumerate() {
+                    for &(x, y) in &placed.tiles {
+                        owners[y * region.width + x] = Some(n);
+                    }
+                }
+                let labels = placement.presents.iter().map(|p| p.present).collect();
+                let how = match tier {
+                    Tier::Fits => "fits, one present per cell",
+                    _ => "fits, found by searching",
+                };
+                (how.to_string(), Some(owners), labels)
+            }
+            Ok(None) => {
+                let reason = match tier {
+                    Tier::CannotFit => format!(
+                        "the presents cover {} tiles but the region only has {}",
+                        present_area(region, &presents),
+                        region.width * region.height
+                    ),
+                    _ => "searching every placement found no way to pack the presents".to_string(),
+                };
+                (format!("doesn't fit, {reason}"), None, Vec::new())
+            }
+            Err(e) => (format!("unknown, {e}"), None, Vec::new()),
+        };
+        let caption = format!(
+            "region {}: {}x{}, {caption}",
+            i + 1,
+            region.width,
+            region.height
+        );
+
+        writeln!(text, "{caption}").unwrap();
+        let tiles = owners
+            .clone()
+            .unwrap_or_else(|| vec![None; region.width * region.height]);
+        text += &draw_region(region.width, region.height, &tiles, |owner| match owner {
+            Some(n) => present_label(labels[n]),
+            None if owners.is_some() => '.',
+            None => 'x',
+        });
+        text.push('\n');
+
+        let colour = if owners.is_some() { "black" } else { "#cc0000" };
+        writeln!(
+            svg,
+            r#"<text x="{SVG_MARGIN}" y="{}" fill="{colour}">{caption}</text>"#,
+            y + SVG_CAPTION_HEIGHT - 8
+        )
+        .unwrap();
+        y += SVG_CAPTION_HEIGHT;
+        svg_region(
+            &mut svg,
+            SVG_MARGIN,
+            y,
+            region,
+            &tiles,
+            &labels,
+            owners.is_some(),
+        );
+        y += region.height * SVG_TILE + SVG_MARGIN;
+    }
+
+    let summary = format!("{} regions: {}", regions.len(), TierCounts::new(&tiers));
+    writeln!(text, "{summary}").unwrap();
+    writeln!(
+        svg,
+        r#"<text x="{SVG_MARGIN}" y="{}">{summary}</text>"#,
+        y + SVG_CAPTION_HEIGHT - 8
+    )
+    .unwrap();
+    y += SVG_CAPTION_HEIGHT + SVG_MARGIN;
+
+    let svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{svg_width}\" height=\"{y}\" \
+        font-family=\"monospace\" font-size=\"12\">\n\
+        <rect x=\"0\" y=\"0\" width=\"{svg_width}\" height=\"{y}\" fill=\"white\"/>\n{svg}</svg>\n"
+    );
+    Ok(Visualization { text, svg })
+}
+
+/// Draw a region as text, with `label` giving the character for each tile from the placed present
+/// that covers it, if any. Tiles are separated by spaces, or by lines where they're covered by
+/// different presents.
+fn draw_region(
+    width: usize,
+    height: usize,
+    owners: &[Option<usize>],
+    label: impl Fn(Option<usize>) -> char,
+) -> String {
+    let owner = |x: i64, y: i64| tile_owner(width, height, owners, x, y);
+    let mut out = String::new();
+    for row in 0..=2 * height as i64 {
+        for column in 0..=2 * width as i64 {
+            let (x, y) = (column / 2, row / 2);
+            let c = match (column % 2, row % 2) {
+                (1, 1) => label(owners[y as usize * width + x as usize]),
+                (0, 1) if owner(x - 1, y) != owner(x, y) => '|',
+                (1, 0) if owner(x, y - 1) != owner(x, y) => '-',
+                (0, 0) => {
+                    // Where four tiles meet
+                    let (top_left, top_right) = (owner(x - 1, y - 1), owner(x, y - 1));
+                    let (bottom_left, bottom_right) = (owner(x - 1, y), owner(x, y));
+                    let vertical = top_left != top_right || bottom_left != bottom_right;
+                    let horizontal = top_left != bottom_left || top_right != bottom_right;
+                    match (vertical, horizontal) {
+                        (true, true) => '+',
+                        (true, false) => '|',
+                        (false, true) => '-',
+                        (false, false) => ' ',
+                    }
+                }
+                _ => ' ',
+            };
+            out.push(c);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Draw a region as SVG with its top left corner at (`left`, `top`), colouring each tile by the
+/// present covering it, or in red if the presents don't fit
+fn svg_region(
+    svg: &mut String,
+    left: usize,
+    top: usize,
+    region: &Region,
+    owners: &[Option<usize>],
+    labels: &[usize],
+    fits: bool,
+) {
+    let (width, height) = (region.width, region.height);
+    for (i, owner) in owners.iter().enumerate() {
+        let (x, y) = (left + i % width * SVG_TILE, top + i / width * SVG_TILE);
+        let fill = match owner {
+            Some(n) => format!("hsl({}, 70%, 75%)", labels[*n] * 47 % 360),
+            None if fits => "#eeeeee".to_string(),
+            None => "#f4cccc".to_string(),
+        };
+        writeln!(
+            svg,
+            r#"<rect x="{x}" y="{y}" width="{SVG_TILE}" height="{SVG_TILE}" fill="{fill}"/>"#
+        )
+        .unwrap();
+        if let Some(n) = owner {
+            writeln!(
+                svg,
+                r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+                x + SVG_TILE / 2,
+                y + SVG_TILE - 4,
+                labels[*n]
+            )
+            .unwrap();
+        }
+    }
+
+    // Lines between tiles covered by different presents, and around the region
+    let owner = |x: i64, y: i64| tile_owner(width, height, owners, x, y);
+    let mut line = |x1: usize, y1: usize, x2: usize, y2: usize| {
+        writeln!(
+            svg,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black" stroke-width="1.5"/>"#,
+            left + x1 * SVG_TILE,
+            top + y1 * SVG_TILE,
+            left + x2 * SVG_TILE,
+            top + y2 * SVG_TILE
+        )
+        .unwrap();
+    };
+    for y in 0..=height {
+        for x in 0..=width {
+            let (tx, ty) = (x as i64, y as i64);
+            if y < height && owner(tx - 1, ty) != owner(tx, ty) {
+                line(x, y, x, y + 1);
+            }
+            if x < width && owner(tx, ty - 1) != owner(tx, ty) {
+                line(x, y, x + 1, y);
+            }
+        }
+    }
+}
+
+/// The placed present covering the tile, if any, or None if the tile is outside the region, so
+/// that the edge of the region always has a border
+fn tile_owner(
+    width: usize,
+    height: usize,
+    owners: &[Option<usize>],
+    x: i64,
+    y: i64,
+) -> Option<Option<usize>> {
+    let inside = (0..width as i64).contains(&x) && (0..height as i64).contains(&y);
+    inside.then(|| owners[y as usize * width + x as usize])
+}
+
+/// The character for a present's index: a digit, then lowercase letters after 9
+fn present_label(present: usize) -> char {
+    char::from_digit(present as u32 % 36, 36).unwrap()
+}
+
+/// Check that the input is numbered presents of any shape then regions like
+/// `12x5: 1 0 1 0 2 2`, with a count for every present
+pub fn validate(input: &str) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    // Blocks of lines separated by blank lines, with the line number each block starts on
+    let mut blocks: Vec<(usize, Vec<&str>)> = Vec::new();
+    let mut in_block = false;
+    for (n, line) in validate::lines(input) {
+        if line.is_empty() {
+            in_block = false;
+        } else if in_block {
+            blocks.last_mut().unwrap().1.push(line);
+        } else {
+            blocks.push((n, vec![line]));
+            in_block = true;
+        }
+    }
+    let Some((regions_start, regions)) = blocks.pop() else {
+        violations.push(Violation::line(1, "expected presents, then regions"));
+        return violations;
+    };
+
+    let mut present_count = 0;
+    for (start, lines) in &blocks {
+        let header = lines[0];
+        let index = header.strip_suffix(':').and_then(|index| {
+            validate::number::<usize>(&mut violations, *start, header, index, "present index")
+        });
+        match index {
+            None if !header.ends_with(':') => {
+                violations.push(Violation::line(*start, "expected a present index like 0:"))
+            }
+            Some(index) if index != present_count => violations.push(Violation::line(
+                *start,
+                format!("expected present {present_count}, found {index}"),
+            )),
+            _ => {}
+        }
+
+        let shape = &lines[1..];
+        for (i, row) in shape.iter().enumerate() {
+            if let Some((j, c)) = row
+                .chars()
+                .enumerate()
+                .find(|(_, c)| !matches!(c, '#' | '.'))
+            {
+                violations.push(Violation::at(
+                    start + 1 + i,
+                    j + 1,
+                    format!("unexpected {c:?}"),
+                ));
+            }
+        }
+        if shape.iter().all(|row| !row.contains('#')) {
+            violations.push(Violation::line(*start, "present has no tiles"));
+        }
+        present_count += 1;
+    }
+    if present_count == 0 {
+        violations.push(Violation::line(1, "expected presents before the regions"));
+    }
+
+    for (i, line) in regions.iter().enumerate() {
+        let n = regions_start + i;
+        let Some((width, height, counts)) = line.split_once(": ").and_then(|(size, counts)| {
+            let (width, height) = size.split_once('x')?;
+            Some((width, height, counts))
+        }) else {
+            violations.push(Violation::line(
+                n,
+                "expected a region like 12x5: 1 0 1 0 2 2",
+            ));
+            continue;
+        };
+        validate::number::<usize>(&mut violations, n, line, width, "width");
+        validate::number::<usize>(&mut violations, n, line, height, "height");
+        let counts: Vec<&str> = counts.split_whitespace().collect();
+        for count in &counts {
+            validate::number::<usize>(&mut violations, n, line, count, "present count");
+        }
+        if counts.len() != present_count {
+            violations.push(Violation::line(
+                n,
+                format!(
+                    "{} present counts, but there are {present_count} presents",
+                    counts.len()
+                ),
+            ));
+        }
+    }
+    violations
+}
+
+/// Six presents and `size` regions about the size of those in the puzzle input. As in the puzzle
+/// input, each region either has a 3x3 square for every present or too few tiles for them.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const PRESENT_COUNT: usize = 6;
+    let mut input = String::new();
+    let sizes: Vec<usize> = (0..PRESENT_COUNT)
+        .map(|i| generate_present(rng, &mut input, i, 3, 3, 5..=7))
+        .collect();
+
+    for _ in 0..size {
+        let (width, height) = (rng.range(35..=50), rng.range(35..=50));
+        let mut counts = [0; PRESENT_COUNT];
+        if rng.chance(1, 2) {
+            let squares = (width / 3) * (height / 3);
+            for _ in 0..rng.range(squares * 3 / 4..=squares) {
+                counts[rng.range(0..=PRESENT_COUNT - 1)] += 1;
+            }
+        } else {
+            let mut tiles = 0;
+            while tiles <= width * height {
+                let present = rng.range(0..=PRESENT_COUNT - 1);
+                counts[present] += 1;
+                tiles += sizes[present];
+            }
+        }
+        input += &format!(
+            "{width}x{height}: {}\n",
+            counts.map(|n| n.to_string()).join(" ")
+        );
+    }
+    input
+}
+
+/// Add a random present of the given size to the input, returning how many tiles it has
+fn generate_present(
+    rng: &mut Rng,
+    input: &mut String,
+    index: usize,
+    width: usize,
+    height: usize,
+    tile_count: RangeInclusive<usize>,
+) -> usize {
+    let tiles = loop {
+        let tiles: Vec<bool> = (0..width * height).map(|_| rng.chance(2, 3)).collect();
+        if tile_count.contains(&tiles.iter().filter(|&&tile| tile).count()) {
+            break tiles;
+        }
+    };
+
+    *input += &format!("{index}:\n");
+    for row in tiles.chunks(width) {
+        let row: String = row
+            .iter()
+            .map(|&tile| if tile { '#' } else { '.' })
+            .collect();
+        *input += &format!("{row}\n");
+    }
+    input.push('\n');
+    tiles.iter().filter(|&&tile| tile).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::{generate::Rng, testing::check_against_reference};
+
+    const EXAMPLE_INPUT: &str = include_str!("../inputs/12.ex");
+
+    /// Check that the placement has the region's presents, each in one of its orientations,
+    /// inside the region and not overlapping
+    fn check_placement(placement: &Placement, region: &Region, presents: &Presents) {
+        let mut counts = vec![0; region.counts.len()];
+        let mut filled = HashSet::new();
+        for placed in &placement.presents {
+            counts[placed.present] += 1;
+            let mut tiles: Vec<(i64, i64)> = placed
+                .tiles
+                .iter()
+                .map(|&(x, y)| {
+                    assert!(x < region.width && y < region.height);
+                    assert!(filled.insert((x, y)), "tile {x},{y} covered twice");
+                    (x as i64, y as i64)
+                })
+                .collect();
+            tiles.sort_unstable_by_key(|&(x, y)| (y, x));
+            let (first_x, first_y) = tiles[0];
+            let shape: Shape = tiles
+                .iter()
+                .map(|&(x, y)| (x - first_x, y - first_y))
+                .collect();
+            assert!(presents[&placed.present].orientations().contains(&shape));
+        }
+        assert_eq!(region.counts, counts);
+    }
+
+    /// The naive solver is only checked on the regions that the presents fit in, as ruling out the
+    /// last region takes it a long time in a debug build
+    #[test]
+    fn test_pack_example() {
+        let (presents, regions) = Day12.parse(EXAMPLE_INPUT).unwrap();
+        for region in &regions[..2] {
+            assert!(fits_naive(region, &presents).unwrap());
+            let placement = pack(region, &presents).unwrap().unwrap();
+            check_placement(&placement, region, &presents);
+        }
+        assert_eq!(None, pack(&regions[2], &presents).unwrap());
+    }
+
+    /// Regions of any size, where the presents often have to be fitted together more tightly than
+    /// one per 3x3 square
+    #[test]
+    fn test_pack_matches_naive() {
+        let mut rng = Rng::new(12);
+        for _ in 0..300 {
+            let (presents, regions) = Day12.parse(&generate_small(&mut rng, false)).unwrap();
+            for region in &regions {
+                let placement = pack(region, &presents).unwrap();
+                assert_eq!(fits_naive(region, &presents).unwrap(), placement.is_some());
+                if let Some(placement) = placement {
+                    check_placement(&placement, region, &presents);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_shape() {
+        let present: Present = "....\n.##.\n.#..\n.###".parse().unwrap();
+        assert_eq!(6, present.area());
+        assert_eq!((3, 3), present.bounding_box());
+        let flipped: Present = "###\n..#\n.##".parse().unwrap();
+        assert_eq!(present.canonical(), flipped.canonical());
+        let other: Present = "######".parse().unwrap();
+        assert_ne!(present.canonical(), other.canonical());
+        assert!("#x".parse::<Present>().is_err());
+    }
+
+    #[test]
+    fn test_pack_mixed_sizes() {
+        // The second region has room for all of the tiles, but not in the right places
+        let input = "0:\n####\n\n1:\n#\n#\n\n2:\n.#.\n###\n\n4x3: 1 1 1\n4x3: 1 2 1\n";
+        let (presents, regions) = Day12.parse(input).unwrap();
+        let placement = pack(&regions[0], &presents).unwrap().unwrap();
+        check_placement(&placement, &regions[0], &presents);
+        assert_eq!(None, pack(&regions[1], &presents).unwrap());
+    }
+
+    #[test]
+    fn test_draw_region() {
+        let owners = [Some(0), Some(0), Some(1), None, None, Some(1)];
+        let labels = ['a', 'b'];
+        let drawing = draw_region(3, 2, &owners, |owner| owner.map_or('.', |n| labels[n]));
+        assert_eq!("+---+-+\n|a a|b|\n+---+ |\n|. .|b|\n+---+-+\n", drawing);
+    }
+
+    #[test]
+    fn test_visualize() {
+        let visualization = visualize(EXAMPLE_INPUT).unwrap();
+        let captions: Vec<&str> = visualization
+            .text
+            .lines()
+            .filter(|line| line.starts_with("region"))
+            .collect();
+        assert_eq!(
+            vec![
+                "region 1: 4x4, fits, found by searching",
+                "region 2: 12x5, fits, found by searching",
+                "region 3: 12x5, doesn't fit, searching every placement found no way to pack the \
+                presents",
+            ],
+            captions
+        );
+        assert!(
+            visualization
+                .text
+                .ends_with("3 regions: 0 fit, 0 can't fit and 3 need searching\n")
+        );
+        assert!(visualization.svg.starts_with("<svg"));
+        assert!(visualization.svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn test_classify() {
+        let input = "0:\n###\n#..\n###\n\n1:\n##\n\n6x3: 1 1\n6x3: 3 0\n4x4: 1 2\n";
+        let (presents, regions) = Day12.parse(input).unwrap();
+        let tiers = classify_all(&presents, &regions).unwrap();
+        assert_eq!(vec![Tier::Fits, Tier::CannotFit, Tier::Unknown], tiers);
+        assert_eq!(
+            TierCounts {
+                fits: 1,
+                cannot_fit: 1,
+                unknown: 1
+            },
+            TierCounts::new(&tiers)
+        );
+
+        // The search settles the unknown region, but the bounds alone can't
+        assert_eq!(
+            Answer::from(2_usize),
+            Day12.part1(&(presents.clone(), regions.clone())).unwrap()
+        );
+        assert!(Day12Bounds.part1(&(presents, regions)).is_err());
+    }
+
+    #[test]
+    fn test_bounds_matches_naive() {
+        check_against_reference(&Day12Bounds, &Day12Naive, 300, |rng| {
+            generate_small(rng, true)
+        });
+    }
+
+    #[test]
+    fn test_orientations() {
+        let present: Present = "###\n#..\n#..".parse().unwrap();
+        assert_eq!(4, present.orientations().len());
+        let present: Present = "##.\n.##\n...".parse().unwrap();
+        assert_eq!(4, present.orientations().len());
+    }
+
+    /// Regions that either have a 3x3 square for every present, or too few tiles for all of the
+    /// presents, as in the puzzle input
+    #[test]
+    fn test_matches_naive() {
+        check_against_reference(&Day12, &Day12Naive, 300, |rng| generate_small(rng, true));
+    }
+
+    /// Random presents and small regions. If `clear_cut` is set, the presents are 3x3 and each
+    /// region either has a 3x3 square for every present or too few tiles for them. Otherwise the
+    /// presents are up to 4x4.
+    fn generate_small(rng: &mut Rng, clear_cut: bool) -> String {
+        let mut input = String::new();
+        let present_count = rng.range(1..=3);
+        let sizes: Vec<usize> = (0..present_count)
+            .map(|i| {
+                let (width, height) = match clear_cut {
+                    true => (3, 3),
+                    false => (rng.range(1..=4), rng.range(1..=4)),
+                };
+                generate_present(rng, &mut input, i, width, height, 1..=width * height)
+            })
+            .collect();
+
+        for _ in 0..rng.range(1..=3) {
+            let (width, height, counts) = loop {
+                let width = rng.range(3..=6);
+                let height = rng.range(3..=6);
+                let counts: Vec<usize> = (0..present_count).map(|_| rng.range(0..=2)).collect();
+                let squares = (width / 3) * (height / 3);
+                let total: usize = counts.iter().sum();
+                let tiles: usize = counts.iter().zip(&sizes).map(|(n, size)| n * size).sum();
+                if !clear_cut || total <= squares || tiles > width * height {
+                    break (width, height, counts);
+                }
+            };
+            let counts: Vec<String> = counts.iter().map(usize::to_string).collect();
+            input += &format!("{width}x{height}: {}\n", counts.join(" "));
+        }
+        input
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = "0:\n###\n#x.\n###\n\n2:\n##\n##\n\n6x3: 2 0\n6x6: 1\n";
+        let violations: Vec<String> = validate(input)
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "line 3, column 2: unexpected 'x'",
+                "line 6: expected present 1, found 2",
+                "line 11: 1 present counts, but there are 2 presents",
+            ],
+            violations
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use std::{fmt::Display, path::PathBuf, str::FromStr};
+
+/// Error produced while reading, parsing or solving a day's puzzle input
+#[derive(Debug)]
+pub struct AocError {
+    day: Option<usize>,
+    kind: ErrorKind,
+}
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// The input could not be read. A path of None means stdin.
+    Input {
+        path: Option<PathBuf>,
+        source: std::io::Error,
+    },
+    /// A line of the input was malformed. Line numbers start at 1.
+    Parse {
+        line: usize,
+        text: String,
+        reason: String,
+    },
+    /// The input parsed, but breaks an assumption the solver relies on to find an answer
+    Unsolvable { reason: String },
+    /// A parameter given for the input (see [`crate::answers::Params`]) was invalid
+    Param { name: String, reason: String },
+}
+
+impl AocError {
+    pub fn input(day: usize, path: Option<PathBuf>, source: std::io::Error) -> Self {
+        Self {
+            day: Some(day),
+            kind: ErrorKind::Input { path, source },
+        }
+    }
+
+    pub fn parse(line: usize, text: &str, reason: impl Display) -> Self {
+        Self {
+            day: None,
+            kind: ErrorKind::Parse {
+                line,
+                text: text.to_string(),
+                reason: reason.to_string(),
+            },
+        }
+    }
+
+    pub fn unsolvable(reason: impl Display) -> Self {
+        Self {
+            day: None,
+            kind: ErrorKind::Unsolvable {
+                reason: reason.to_string(),
+            },
+        }
+    }
+
+    pub fn param(name: &str, reason: impl Display) -> Self {
+        Self {
+            day: None,
+            kind: ErrorKind::Param {
+                name: name.to_string(),
+                reason: reason.to_string(),
+            },
+        }
+    }
+
+    /// Attach the day to the error. Solvers don't know which day they are, so the runner does this.
+    pub fn for_day(mut self, day: usize) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    /// Shift the line number of a parse error, for errors from a section that doesn't start on the
+    /// first line of the input.
+    pub fn offset_lines(mut self, offset: usize) -> Self {
+        if let ErrorKind::Parse { ref mut line, .. } = self.kind {
+            *line += offset;
+        }
+        self
+    }
+
+    /// Say where in the input the error happened, such as which item the solver was working on,
+    /// keeping its kind. Input errors already name the file, so they're left as they are.
+    pub fn context(mut self, context: impl Display) -> Self {
+        match self.kind {
+            ErrorKind::Parse { ref mut reason, .. }
+            | ErrorKind::Unsolvable { ref mut reason }
+            | ErrorKind::Param { ref mut reason, .. } => *reason = format!("{context}: {reason}"),
+            ErrorKind::Input { .. } => {}
+        }
+        self
+    }
+
+    pub fn day(&self) -> Option<usize> {
+        self.day
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day}: ")?;
+        }
+
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::Input {
+                path: Some(path),
+                source,
+            } => write!(f, "could not read input from {}: {source}", path.display()),
+            ErrorKind::Input { path: None, source } => {
+                write!(f, "could not read input from stdin: {source}")
+            }
+            ErrorKind::Parse { line, text, reason } => {
+                write!(f, "line {line}: {reason}: {text:?}")
+            }
+            ErrorKind::Unsolvable { reason } => write!(f, "no solution: {reason}"),
+            ErrorKind::Param { name, reason } => write!(f, "parameter {name}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ErrorKind::Input { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Parse each line of `input` with `FromStr`, reporting the line number and text of the first line
+/// that fails. Trailing whitespace is ignored, but blank lines within the input are not skipped.
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>, AocError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    input
+        .trim_end()
+        .lines()
+        .enumerate()
+        .map(|(i, line)| line.parse().map_err(|e| AocError::parse(i + 1, line, e)))
+        .collect()
+}
+
+/// Parse a single number, with an error message naming what the number was supposed to be
+pub fn parse_num<T>(s: &str, what: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    s.trim()
+        .parse()
+        .map_err(|e| format!("invalid {what} {s:?} ({e})"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines_reports_line_number() {
+        let err = parse_lines::<u32>("1\n2\nthree\n4\n").unwrap_err();
+        match err.kind() {
+            ErrorKind::Parse { line, text, .. } => {
+                assert_eq!(3, *line);
+                assert_eq!("three", text);
+            }
+            kind => panic!("unexpected error kind {kind:?}"),
+        }
+
+        let err = err.offset_lines(10).for_day(5);
+        assert_eq!(Some(5), err.day());
+        assert!(err.to_string().starts_with("day 5: line 13: "));
+    }
+
+    #[test]
+    fn test_context() {
+        let err = AocError::unsolvable("too big").context("region 2");
+        assert!(matches!(err.kind(), ErrorKind::Unsolvable { .. }));
+        assert_eq!("no solution: region 2: too big", err.to_string());
+    }
+}
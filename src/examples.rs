@@ -0,0 +1,162 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use crate::{
+    Aoc, DayArgs, Part, TimedSolution, error::AocError, input::InputSource, load_answers, run_day,
+};
+
+#[derive(clap::Args)]
+pub struct ExamplesArgs {
+    #[command(flatten)]
+    pub days: DayArgs,
+}
+
+/// The result of running a solver on one example
+pub struct ExampleResult {
+    pub path: PathBuf,
+    pub result: Result<TimedSolution, AocError>,
+}
+
+pub fn run(args: &ExamplesArgs) -> ExitCode {
+    if args.days.input.is_some() {
+        eprintln!("error: examples are always read from the inputs directory");
+        return ExitCode::FAILURE;
+    }
+
+    let days = match args.days.days() {
+        Ok(days) => days,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut any_failed = false;
+    for (day, aoc) in days {
+        let results = match run_examples(day, aoc, &args.days.inputs_dir(), &args.days.parts()) {
+            Ok(results) => results,
+            Err(e) => {
+                println!("day {day:2}: error: could not list examples: {e}");
+                any_failed = true;
+                continue;
+            }
+        };
+
+        for example in results {
+            let name = example
+                .path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            match &example.result {
+                Ok(solution) => {
+                    println!("day {day:2} {name:8} {solution}");
+                    for mismatch in solution.mismatches() {
+                        eprintln!("day {day:2} {name}: wrong answer: {mismatch}");
+                        any_failed = true;
+                    }
+                }
+                Err(e) => {
+                    println!("day {day:2} {name:8} error: {}", e.kind());
+                    any_failed = true;
+                }
+            }
+        }
+    }
+
+    if any_failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Run the solver on every example for `day` in `dir`, checking each against its answers file.
+/// Only the parts with known answers are run, as an example is often only valid for one part,
+/// unless none are known.
+pub fn run_examples(
+    day: usize,
+    aoc: &dyn Aoc,
+    dir: &Path,
+    parts: &[Part],
+) -> io::Result<Vec<ExampleResult>> {
+    Ok(find_examples(dir, day)?
+        .into_iter()
+        .map(|path| {
+            let source = InputSource::File(path.clone());
+            let result = load_answers(day, &source).and_then(|answers| {
+                let known: Vec<Part> = parts
+                    .iter()
+                    .copied()
+                    .filter(|&part| answers.get(part).is_some())
+                    .collect();
+                let parts = if known.is_empty() { parts } else { &known };
+                run_day(day, aoc, &source, parts, false)
+            });
+            ExampleResult { path, result }
+        })
+        .collect())
+}
+
+/// The examples for `day` in `dir`: `NN.ex`, along with any others named like `NN.2.ex`
+pub fn find_examples(dir: &Path, day: usize) -> io::Result<Vec<PathBuf>> {
+    let prefix = format!("{day:02}.");
+    let mut examples = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_example = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".ex"));
+        if is_example {
+            examples.push(path);
+        }
+    }
+
+    examples.sort();
+    Ok(examples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DEFAULT_YEAR, REGISTRY};
+
+    /// Run every example for every day, so that adding an example with its answers file is enough
+    /// to test it
+    #[test]
+    fn test_examples() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        let mut problems = Vec::new();
+        for (day, aoc) in REGISTRY.select(DEFAULT_YEAR, None, None).unwrap() {
+            for example in run_examples(day, aoc, &dir, &[Part::One, Part::Two]).unwrap() {
+                let path = example.path.display();
+                match example.result {
+                    Ok(solution) => problems.extend(
+                        solution
+                            .mismatches()
+                            .into_iter()
+                            .map(|mismatch| format!("{path}: wrong answer: {mismatch}")),
+                    ),
+                    Err(e) => problems.push(format!("{path}: error: {}", e.kind())),
+                }
+            }
+        }
+
+        assert!(problems.is_empty(), "{}", problems.join("\n"));
+    }
+
+    #[test]
+    fn test_find_examples() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        let names: Vec<_> = find_examples(&dir, 11)
+            .unwrap()
+            .into_iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(vec!["11.2.ex", "11.ex"], names);
+    }
+}
@@ -0,0 +1,236 @@
+//! Folded stacks and a small SVG flamegraph renderer, so that profiles can be viewed without
+//! installing any other tools.
+//!
+//! A profile is a set of stacks, each a list of frames from the root down, with a weight such as a
+//! number of samples. The folded format has one `root;child;leaf weight` line per stack, which is
+//! what inferno and the other flamegraph tools read.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+/// The weight of each stack, keyed by its frames from the root down
+pub type Stacks = BTreeMap<Vec<String>, u64>;
+
+const WIDTH: f64 = 1200.0;
+const FRAME_HEIGHT: f64 = 16.0;
+const MARGIN: f64 = 10.0;
+const TITLE_HEIGHT: f64 = 30.0;
+const FONT_SIZE: f64 = 12.0;
+/// Roughly the width of one character at the font size used for frame names
+const CHAR_WIDTH: f64 = 7.0;
+/// Frames narrower than this aren't drawn, since they couldn't be seen anyway
+const MIN_FRAME_WIDTH: f64 = 0.1;
+
+/// The stacks in the folded format, one line per stack
+pub fn folded(stacks: &Stacks) -> String {
+    let mut out = String::new();
+    for (frames, weight) in stacks {
+        writeln!(out, "{} {weight}", frames.join(";")).unwrap();
+    }
+    out
+}
+
+/// A frame and everything called from it, merged across all stacks that pass through it
+#[derive(Debug, Default)]
+struct Node {
+    total: u64,
+    children: BTreeMap<String, Node>,
+}
+
+impl Node {
+    fn from_stacks(stacks: &Stacks) -> Self {
+        let mut root = Node::default();
+        for (frames, &weight) in stacks {
+            let mut node = &mut root;
+            node.total += weight;
+            for frame in frames {
+                node = node.children.entry(frame.clone()).or_default();
+                node.total += weight;
+            }
+        }
+        root
+    }
+
+    fn depth(&self) -> usize {
+        self.children
+            .values()
+            .map(|child| child.depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Render the stacks as an SVG flamegraph, with the root at the bottom and each frame as wide as
+/// its share of the total weight. Hovering over a frame shows its name and weight in `unit`.
+pub fn svg(stacks: &Stacks, title: &str, unit: &str) -> String {
+    let root = Node::from_stacks(stacks);
+    let height = TITLE_HEIGHT + root.depth() as f64 * FRAME_HEIGHT + MARGIN;
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" viewBox="0 0 {WIDTH} {height}" font-family="monospace" font-size="{FONT_SIZE}">"#
+    )
+    .unwrap();
+    writeln!(
+        out,
+        r##"<rect x="0" y="0" width="{WIDTH}" height="{height}" fill="#eeeeee"/>"##
+    )
+    .unwrap();
+    writeln!(
+        out,
+        r#"<text x="{}" y="{}" text-anchor="middle" font-size="{}">{}</text>"#,
+        WIDTH / 2.0,
+        TITLE_HEIGHT * 0.6,
+        FONT_SIZE + 4.0,
+        escape(title)
+    )
+    .unwrap();
+
+    if root.total > 0 {
+        let scale = (WIDTH - 2.0 * MARGIN) / root.total as f64;
+        let bottom = height - MARGIN;
+        let mut x = MARGIN;
+        for (name, child) in &root.children {
+            write_frame(&mut out, name, child, x, bottom, scale, root.total, unit);
+            x += child.total as f64 * scale;
+        }
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Write a frame with its bottom edge at `bottom`, then the frames it calls on top of it
+#[allow(clippy::too_many_arguments)]
+fn write_frame(
+    out: &mut String,
+    name: &str,
+    node: &Node,
+    x: f64,
+    bottom: f64,
+    scale: f64,
+    grand_total: u64,
+    unit: &str,
+) {
+    let width = node.total as f64 * scale;
+    if width < MIN_FRAME_WIDTH {
+        return;
+    }
+    let y = bottom - FRAME_HEIGHT;
+    let percent = node.total as f64 * 100.0 / grand_total as f64;
+
+    writeln!(
+        out,
+        r#"<g><title>{} ({} {unit}, {percent:.2}%)</title><rect x="{x:.2}" y="{y:.2}" width="{width:.2}" height="{}" fill="{}" rx="2"/>"#,
+        escape(name),
+        node.total,
+        FRAME_HEIGHT - 1.0,
+        colour(name)
+    )
+    .unwrap();
+    let label = label(name, width);
+    if !label.is_empty() {
+        writeln!(
+            out,
+            r#"<text x="{:.2}" y="{:.2}">{}</text>"#,
+            x + 3.0,
+            y + FRAME_HEIGHT - 4.0,
+            escape(&label)
+        )
+        .unwrap();
+    }
+    out.push_str("</g>\n");
+
+    let mut child_x = x;
+    for (child_name, child) in &node.children {
+        write_frame(out, child_name, child, child_x, y, scale, grand_total, unit);
+        child_x += child.total as f64 * scale;
+    }
+}
+
+/// As much of the name as fits in the width, ending in `..` if it had to be cut short
+fn label(name: &str, width: f64) -> String {
+    let fits = ((width - 6.0) / CHAR_WIDTH).max(0.0) as usize;
+    if name.chars().count() <= fits {
+        name.to_string()
+    } else if fits >= 3 {
+        name.chars().take(fits - 2).chain("..".chars()).collect()
+    } else {
+        String::new()
+    }
+}
+
+/// A warm colour picked from the name, so that the same function has the same colour everywhere
+fn colour(name: &str) -> String {
+    // FNV-1a
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3)
+    });
+    let red = 205 + (hash % 50);
+    let green = (hash >> 8) % 230;
+    let blue = (hash >> 16) % 55;
+    format!("rgb({red},{green},{blue})")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stacks(lines: &[(&str, u64)]) -> Stacks {
+        lines
+            .iter()
+            .map(|(stack, weight)| (stack.split(';').map(String::from).collect(), *weight))
+            .collect()
+    }
+
+    #[test]
+    fn test_folded() {
+        let stacks = stacks(&[
+            ("day08;pt1", 30),
+            ("day08;parse", 5),
+            ("day08;pt1;sort", 20),
+        ]);
+        assert_eq!(
+            "day08;parse 5\nday08;pt1 30\nday08;pt1;sort 20\n",
+            folded(&stacks)
+        );
+    }
+
+    #[test]
+    fn test_merge_stacks() {
+        let root = Node::from_stacks(&stacks(&[("a;b", 3), ("a;c", 1), ("a;b;d", 2), ("e", 4)]));
+        assert_eq!(10, root.total);
+        assert_eq!(3, root.depth());
+        assert_eq!(6, root.children["a"].total);
+        assert_eq!(5, root.children["a"].children["b"].total);
+        assert_eq!(2, root.children["a"].children["b"].children["d"].total);
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = svg(
+            &stacks(&[("main;Vec<T>::push", 3), ("main", 1)]),
+            "day 1",
+            "samples",
+        );
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("<title>main (4 samples, 100.00%)</title>"));
+        assert!(svg.contains("<title>Vec&lt;T&gt;::push (3 samples, 75.00%)</title>"));
+        assert_eq!(2, svg.matches("<g>").count());
+    }
+
+    #[test]
+    fn test_label() {
+        assert_eq!("main", label("main", 100.0));
+        assert_eq!("day0..", label("day08::part1", 50.0));
+        assert_eq!("", label("day08::part1", 10.0));
+    }
+}
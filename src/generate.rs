@@ -0,0 +1,122 @@
+use std::{
+    ops::RangeInclusive,
+    process::ExitCode,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{DEFAULT_YEAR, REGISTRY};
+
+#[derive(clap::Args)]
+pub struct GenerateArgs {
+    /// The day to generate an input for
+    day: usize,
+
+    /// The year of the day
+    #[arg(long, default_value_t = DEFAULT_YEAR)]
+    year: u16,
+
+    /// Roughly how many items (lines, points, machines and so on) the input has
+    #[arg(long, default_value_t = 100)]
+    size: usize,
+
+    /// Seed for the random number generator, so that the same input can be made again. If
+    /// omitted, a random seed is used and printed to stderr.
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+pub fn run(args: &GenerateArgs) -> ExitCode {
+    let generator = match REGISTRY.generator(args.year, args.day) {
+        Ok(generator) => generator,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if args.size == 0 {
+        eprintln!("error: size must be at least 1");
+        return ExitCode::FAILURE;
+    }
+
+    let seed = args.seed.unwrap_or_else(|| {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or_default();
+        eprintln!("seed {seed}");
+        seed
+    });
+
+    print!("{}", generator(&mut Rng::new(seed), args.size));
+    ExitCode::SUCCESS
+}
+
+/// A small seeded pseudo random number generator (SplitMix64), so that every generated input can
+/// be made again from its seed
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in the inclusive range
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let span = (range.end() - range.start()) as u64 + 1;
+        range.start() + (self.next_u64() % span) as usize
+    }
+
+    /// True with probability `numerator / denominator`
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.next_u64() % denominator < numerator
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..=i));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::Params;
+
+    /// Every generated input should be accepted by its day's solver, for the days that have a
+    /// generator
+    #[test]
+    fn test_generated_inputs_parse() {
+        for year in REGISTRY.years() {
+            for day in REGISTRY.days(year) {
+                let Ok(generator) = REGISTRY.generator(year, day) else {
+                    continue;
+                };
+                let aoc = REGISTRY.solver(year, day, None).unwrap().aoc;
+                for seed in 0..5 {
+                    let input = generator(&mut Rng::new(seed), 50);
+                    if let Err(e) = aoc.parse_input(&input, &Params::default()) {
+                        panic!("day {day} of {year} seed {seed}: {e}\n{input}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_input() {
+        let generator = REGISTRY.generator(DEFAULT_YEAR, 9).unwrap();
+        assert_eq!(
+            generator(&mut Rng::new(7), 10),
+            generator(&mut Rng::new(7), 10)
+        );
+    }
+}
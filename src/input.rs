@@ -0,0 +1,101 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use crate::{DEFAULT_YEAR, error::AocError};
+
+/// Where to read puzzle input from
+#[derive(Debug, Clone)]
+pub enum InputSource {
+    /// A directory containing one input per day, named `01.in`, `02.in` and so on
+    Dir(PathBuf),
+    /// A single file, used regardless of which day is being run
+    File(PathBuf),
+    /// Standard input
+    Stdin,
+}
+
+impl InputSource {
+    /// Use `path` as a single input file, where `-` means stdin
+    pub fn from_path(path: &Path) -> Self {
+        if path == Path::new("-") {
+            InputSource::Stdin
+        } else {
+            InputSource::File(path.to_owned())
+        }
+    }
+
+    /// The path the input for `day` would be read from, or None for stdin
+    pub fn path_for_day(&self, day: usize) -> Option<PathBuf> {
+        match self {
+            InputSource::Dir(dir) => Some(dir.join(format!("{day:02}.in"))),
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+        }
+    }
+
+    /// The path of the known answers for `day`, next to its input, or None for stdin. The answers
+    /// for `01.in` are in `01.ans`, and for any other file (such as the example `01.ex`) the `.ans`
+    /// extension is added, e.g. `01.ex.ans`.
+    pub fn answers_path(&self, day: usize) -> Option<PathBuf> {
+        let path = self.path_for_day(day)?;
+        if path.extension().is_some_and(|extension| extension == "in") {
+            Some(path.with_extension("ans"))
+        } else {
+            let mut path = path.into_os_string();
+            path.push(".ans");
+            Some(path.into())
+        }
+    }
+
+    pub fn read(&self, day: usize) -> Result<String, AocError> {
+        match self.path_for_day(day) {
+            Some(path) => std::fs::read_to_string(&path)
+                .map_err(|source| AocError::input(day, Some(path), source)),
+            None => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|source| AocError::input(day, None, source))?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+/// Arguments for commands that look at one day's input without running the day
+#[derive(clap::Args)]
+pub struct InputArgs {
+    /// The day whose input to use
+    pub day: usize,
+
+    /// The input file, or `-` for stdin. Defaults to the day's input in the inputs directory.
+    pub file: Option<PathBuf>,
+
+    /// The year of the day
+    #[arg(long, default_value_t = DEFAULT_YEAR)]
+    pub year: u16,
+
+    /// Directory containing the puzzle inputs, used when no file is given
+    #[arg(long, default_value = "inputs")]
+    pub inputs_dir: PathBuf,
+}
+
+impl InputArgs {
+    pub fn source(&self) -> InputSource {
+        match &self.file {
+            Some(path) => InputSource::from_path(path),
+            None if self.year == DEFAULT_YEAR => InputSource::Dir(self.inputs_dir.clone()),
+            None => InputSource::Dir(self.inputs_dir.join(self.year.to_string())),
+        }
+    }
+
+    /// The input's path, or `stdin`, for messages about it
+    pub fn name(&self) -> String {
+        match self.source().path_for_day(self.day) {
+            Some(path) => path.display().to_string(),
+            None => "stdin".to_string(),
+        }
+    }
+}
@@ -1,10 +1,24 @@
 use std::{
+    any::Any,
     fmt::Display,
+    path::PathBuf,
+    process::ExitCode,
+    sync::LazyLock,
     time::{Duration, Instant},
 };
 
-use clap::Parser;
+use answers::{Answers, Params, Verification};
+use clap::{Parser, Subcommand, ValueEnum};
+use error::AocError;
+use input::InputSource;
+use memory::{StageUsage, Tracker};
+use rayon::prelude::*;
+use registry::Registry;
+use report::{Format, Report};
 
+mod answers;
+mod baseline;
+mod bench;
 mod day01;
 mod day02;
 mod day03;
@@ -17,76 +31,645 @@ mod day09;
 mod day10;
 mod day11;
 mod day12;
+mod error;
+mod examples;
+mod flamegraph;
+mod generate;
+mod input;
+mod memory;
+mod parallel;
+mod profile;
+mod registry;
+mod report;
+mod scaffold;
+mod scale;
+#[cfg(test)]
+mod testing;
+mod trace;
+mod validate;
+mod visualize;
+mod watch;
 
+/// Run the Advent of Code solutions, checking their answers and timing each stage
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    days: DayArgs,
+
+    /// Save the answers as the known correct answers (e.g. `inputs/01.ans`) instead of checking
+    /// them
+    #[arg(long)]
+    record: bool,
+
+    /// How to print the results
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+
+    /// Run the days concurrently, still printing the results in day order once all have finished
+    #[arg(long, conflicts_with = "day")]
+    parallel: bool,
+
+    /// Print how long each span inside the solvers took, as a tree per day on stderr
+    #[arg(long)]
+    trace: bool,
+
+    /// Count the allocations made by each stage, and show how many there were, their total size
+    /// and the peak memory allocated at once alongside the times
+    #[arg(long, conflicts_with = "parallel")]
+    allocs: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run each day repeatedly and report timing statistics
+    Bench(bench::BenchArgs),
+    /// Create a new day from the template, along with empty input, example and answers files
+    New(scaffold::NewArgs),
+    /// Re-run a day on its example and input whenever either of them changes
+    Watch(watch::WatchArgs),
+    /// Run every example for each day and check them against their answers files
+    Examples(examples::ExamplesArgs),
+    /// Print a random input for a day
+    Generate(generate::GenerateArgs),
+    /// Time a day on generated inputs of increasing size, and estimate how its time grows
+    Scale(scale::ScaleArgs),
+    /// Run a day repeatedly for a while and write a flamegraph of where the time goes
+    Profile(profile::ProfileArgs),
+    /// Check that an input is in the right format and meets the solver's assumptions, listing
+    /// every problem found
+    Validate(validate::ValidateArgs),
+    /// Draw how a day solves an input, in the terminal and optionally as an SVG
+    Visualize(visualize::VisualizeArgs),
+}
+
+// Arguments selecting which days and parts to run, and where their inputs come from. This is a
+// plain comment rather than a doc comment, since clap would show a doc comment as the description
+// of every command it's flattened into.
+#[derive(clap::Args)]
+struct DayArgs {
     /// The day to run, will run all if omitted
     day: Option<usize>,
+
+    /// The year to run the day(s) from
+    #[arg(long, default_value_t = DEFAULT_YEAR)]
+    year: u16,
+
+    /// Use this alternative solver for the day instead of the default one
+    #[arg(long, requires = "day")]
+    solver: Option<String>,
+
+    /// Directory containing the puzzle inputs, named `01.in`, `02.in` and so on. Inputs for years
+    /// other than the default are in a subdirectory per year, e.g. `2024/01.in`.
+    #[arg(long, default_value = "inputs")]
+    inputs_dir: PathBuf,
+
+    /// Read the input from this file instead of the inputs directory, or `-` for stdin
+    #[arg(long, requires = "day")]
+    input: Option<PathBuf>,
+
+    /// Only run one part, will run both if omitted
+    #[arg(long)]
+    part: Option<Part>,
+
+    /// Use the multithreaded implementations of the solvers that have them, with this many
+    /// threads (0 for one per CPU)
+    #[arg(long)]
+    threads: Option<usize>,
+}
+
+impl DayArgs {
+    fn source(&self) -> InputSource {
+        match &self.input {
+            Some(path) => InputSource::from_path(path),
+            None => InputSource::Dir(self.inputs_dir()),
+        }
+    }
+
+    /// The directory containing the inputs for the selected year
+    fn inputs_dir(&self) -> PathBuf {
+        if self.year == DEFAULT_YEAR {
+            self.inputs_dir.clone()
+        } else {
+            self.inputs_dir.join(self.year.to_string())
+        }
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => vec![Part::One, Part::Two],
+        }
+    }
+
+    /// The selected days, along with their solvers, or a description of what's available if
+    /// there is no such day or solver
+    fn days(&self) -> Result<Vec<(usize, &'static (dyn Aoc + Send + Sync))>, String> {
+        REGISTRY.select(self.year, self.day, self.solver.as_deref())
+    }
 }
 
-static DAYS: [&(dyn Aoc + Send + Sync); 12] = [
-    &day01::Day01,
-    &day02::Day02,
-    &day03::Day03,
-    &day04::Day04,
-    &day05::Day05,
-    &day06::Day06,
-    &day07::Day07,
-    &day08::Day08,
-    &day09::Day09,
-    &day10::Day10,
-    &day11::Day11,
-    &day12::Day12,
-];
-
-fn main() {
+// Declares `DEFAULT_YEAR` and `register_days`, see build.rs
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+static REGISTRY: LazyLock<Registry> = LazyLock::new(|| {
+    let mut registry = Registry::default();
+    register_days(&mut registry);
+    registry
+});
+
+fn main() -> ExitCode {
     let args = Args::parse();
 
-    match args.day {
-        Some(day) => {
-            let aoc = DAYS.get(day - 1).expect("invalid day index");
-            let timed_solution = run_with_timing(*aoc);
-            println!("{timed_solution}");
-        }
-        None => {
-            let mut total_elapsed = Duration::default();
-            for (i, aoc) in DAYS.iter().enumerate() {
-                let timed_solution = run_with_timing(*aoc);
-                total_elapsed += timed_solution.elapsed;
-                println!("day {:2}: {timed_solution}", i + 1);
+    let day_args = match &args.command {
+        None => &args.days,
+        Some(Command::Bench(bench_args)) => &bench_args.days,
+        Some(Command::Watch(watch_args)) => &watch_args.days,
+        Some(Command::Examples(examples_args)) => &examples_args.days,
+        Some(Command::Scale(scale_args)) => &scale_args.days,
+        Some(Command::Profile(profile_args)) => &profile_args.days,
+        Some(Command::New(new_args)) => return scaffold::run(new_args),
+        Some(Command::Generate(generate_args)) => return generate::run(generate_args),
+        Some(Command::Validate(validate_args)) => return validate::run(validate_args),
+        Some(Command::Visualize(visualize_args)) => return visualize::run(visualize_args),
+    };
+    if let Some(threads) = day_args.threads
+        && let Err(e) = parallel::configure(threads)
+    {
+        eprintln!("error: could not start thread pool: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    match &args.command {
+        None => run(
+            &args.days,
+            args.record,
+            args.format,
+            args.parallel,
+            args.trace,
+            args.allocs,
+        ),
+        Some(Command::Bench(bench_args)) => bench::run(bench_args),
+        Some(Command::Watch(watch_args)) => watch::run(watch_args),
+        Some(Command::Examples(examples_args)) => examples::run(examples_args),
+        Some(Command::Scale(scale_args)) => scale::run(scale_args),
+        Some(Command::Profile(profile_args)) => profile::run(profile_args),
+        Some(
+            Command::New(_) | Command::Generate(_) | Command::Validate(_) | Command::Visualize(_),
+        ) => {
+            unreachable!("handled above")
+        }
+    }
+}
+
+fn run(
+    args: &DayArgs,
+    record: bool,
+    format: Format,
+    parallel: bool,
+    trace: bool,
+    allocs: bool,
+) -> ExitCode {
+    let source = args.source();
+    let parts = args.parts();
+    let all_days = args.day.is_none();
+    let days = match args.days() {
+        Ok(days) => days,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if trace {
+        trace::enable();
+    }
+    if allocs {
+        memory::enable();
+    }
+
+    let mut report = Report::new(format, all_days, allocs);
+    let mut total = StageTimes::default();
+    let mut any_failed = false;
+
+    if let Some(header) = report.header() {
+        print!("{header}");
+    }
+
+    let run_one = |&(day, aoc): &(usize, &'static (dyn Aoc + Send + Sync))| {
+        // Spans are recorded on the thread that ran the day, so take them before moving on
+        let result = run_day(day, aoc, &source, &parts, record);
+        (day, result, trace::take())
+    };
+
+    let start = Instant::now();
+    let results: Box<dyn Iterator<Item = _>> = if parallel {
+        Box::new(days.par_iter().map(run_one).collect::<Vec<_>>().into_iter())
+    } else {
+        // Print each day as soon as it finishes
+        Box::new(days.iter().map(run_one))
+    };
+
+    for (day, result, spans) in results {
+        if let Some(row) = report.row(day, &result) {
+            print!("{row}");
+        }
+        if trace {
+            eprintln!("day {day}");
+            for line in trace::tree(&spans).lines() {
+                eprintln!("  {line}");
+            }
+        }
+
+        match &result {
+            Ok(timed_solution) => {
+                total += timed_solution.times();
+                for mismatch in timed_solution.mismatches() {
+                    if all_days {
+                        eprintln!("day {day:2}: wrong answer: {mismatch}");
+                    } else {
+                        eprintln!("wrong answer: {mismatch}");
+                    }
+                    any_failed = true;
+                }
+            }
+            Err(e) => {
+                if !all_days {
+                    eprintln!("error: {e}");
+                }
+                any_failed = true;
             }
-            println!("total elapsed time: {} us", total_elapsed.as_micros());
         }
     }
+
+    let wall_clock = parallel.then(|| start.elapsed());
+    if let Some(footer) = report.footer(total, wall_clock) {
+        print!("{footer}");
+    }
+
+    if any_failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Part {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+}
+
+/// A day's solution, split into stages so that each can be run and timed on its own
+pub trait Solution {
+    /// The puzzle input after parsing, shared by both parts. This can include any precomputation
+    /// that both parts need.
+    type Parsed: 'static;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError>;
+
+    /// Parse the input, using parameters given for it alongside its answers. Days that take
+    /// parameters override this, and the default rejects any parameters.
+    fn parse_with_params(&self, input: &str, params: &Params) -> Result<Self::Parsed, AocError> {
+        params.check_known(&[])?;
+        self.parse(input)
+    }
+
+    fn part1(&self, parsed: &Self::Parsed) -> Result<Answer, AocError>;
+
+    fn part2(&self, parsed: &Self::Parsed) -> Result<Answer, AocError>;
+}
+
+/// Object safe version of [`Solution`], so that days with different parsed types can live in the
+/// same array.
 pub trait Aoc {
-    /// Run the problem, returning the part1 and part2 answers as Strings
-    fn run(&self) -> (String, String);
+    fn parse_input(&self, input: &str, params: &Params) -> Result<Box<dyn Any>, AocError>;
+
+    /// Solve one part, where `parsed` must have come from `parse_input` on the same solution
+    fn solve_part(&self, parsed: &dyn Any, part: Part) -> Result<Answer, AocError>;
+}
+
+impl<T: Solution> Aoc for T {
+    fn parse_input(&self, input: &str, params: &Params) -> Result<Box<dyn Any>, AocError> {
+        Ok(Box::new(self.parse_with_params(input, params)?))
+    }
+
+    fn solve_part(&self, parsed: &dyn Any, part: Part) -> Result<Answer, AocError> {
+        let parsed = parsed
+            .downcast_ref()
+            .expect("parsed input must come from the same solution");
+        match part {
+            Part::One => self.part1(parsed),
+            Part::Two => self.part2(parsed),
+        }
+    }
+}
+
+/// The answer to one part of a day's puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    Text(String),
+    /// The part has no solution yet
+    NotImplemented,
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Use `pad` rather than `write!` so that width and alignment flags are respected
+        match self {
+            Answer::Int(n) => f.pad(&n.to_string()),
+            Answer::UInt(n) => f.pad(&n.to_string()),
+            Answer::Text(s) => f.pad(s),
+            Answer::NotImplemented => f.pad("not implemented"),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Int(n)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Answer::UInt(n)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::UInt(n as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
 }
 
 struct TimedSolution {
+    parse: Duration,
+    pt1: Option<TimedAnswer>,
+    pt2: Option<TimedAnswer>,
+    /// Allocations made by each stage, which are only counted once [`memory::enable`] is called
+    usage: StageUsage,
+}
+
+struct TimedAnswer {
+    answer: Answer,
     elapsed: Duration,
-    pt1: String,
-    pt2: String,
+    verification: Verification,
+}
+
+impl TimedSolution {
+    fn times(&self) -> StageTimes {
+        StageTimes {
+            parse: self.parse,
+            pt1: self.pt1.as_ref().map(|a| a.elapsed).unwrap_or_default(),
+            pt2: self.pt2.as_ref().map(|a| a.elapsed).unwrap_or_default(),
+        }
+    }
+
+    fn answer_mut(&mut self, part: Part) -> Option<&mut TimedAnswer> {
+        match part {
+            Part::One => self.pt1.as_mut(),
+            Part::Two => self.pt2.as_mut(),
+        }
+    }
+
+    /// Check each answer against the known answers, recording them first if `record` is set
+    fn verify(&mut self, answers: &mut Answers, record: bool) {
+        for part in [Part::One, Part::Two] {
+            if let Some(a) = self.answer_mut(part) {
+                if record {
+                    answers.record(part, &a.answer);
+                }
+                a.verification = answers.verify(part, &a.answer);
+            }
+        }
+    }
+
+    /// A description of each answer that doesn't match the known answer
+    fn mismatches(&self) -> Vec<String> {
+        [("pt1", &self.pt1), ("pt2", &self.pt2)]
+            .into_iter()
+            .filter_map(|(name, a)| match a {
+                Some(TimedAnswer {
+                    answer,
+                    verification: Verification::Fail { expected },
+                    ..
+                }) => Some(format!("{name} = {answer}, expected {expected}")),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 impl Display for TimedSolution {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let answer = |a: &Option<TimedAnswer>| match a {
+            Some(a) => a.answer.to_string(),
+            None => "-".to_string(),
+        };
+        let verification = |a: &Option<TimedAnswer>| match a {
+            Some(a) => a.verification.to_string(),
+            None => "-".to_string(),
+        };
+        let elapsed = |a: &Option<TimedAnswer>| match a {
+            Some(a) => a.elapsed.as_micros().to_string(),
+            None => "-".to_string(),
+        };
+
         write!(
             f,
-            "pt1 = {:20} pt2 = {:20} elapsed = {} us",
-            self.pt1,
-            self.pt2,
-            self.elapsed.as_micros()
+            "pt1 = {:20} {:7} pt2 = {:20} {:7} elapsed = {:>6} us (parse = {:>6} us, pt1 = {:>6} us, pt2 = {:>6} us)",
+            answer(&self.pt1),
+            verification(&self.pt1),
+            answer(&self.pt2),
+            verification(&self.pt2),
+            self.times().total().as_micros(),
+            self.parse.as_micros(),
+            elapsed(&self.pt1),
+            elapsed(&self.pt2),
         )
     }
 }
 
-fn run_with_timing(aoc: &dyn Aoc) -> TimedSolution {
+/// Time spent in each stage of a solution
+#[derive(Debug, Clone, Copy, Default)]
+struct StageTimes {
+    parse: Duration,
+    pt1: Duration,
+    pt2: Duration,
+}
+
+impl StageTimes {
+    fn total(&self) -> Duration {
+        self.parse + self.pt1 + self.pt2
+    }
+
+    fn get(&self, stage: Stage) -> Duration {
+        match stage {
+            Stage::Parse => self.parse,
+            Stage::Pt1 => self.pt1,
+            Stage::Pt2 => self.pt2,
+            Stage::Total => self.total(),
+        }
+    }
+}
+
+/// A timed stage of a solution, or the total across all stages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Stage {
+    Parse,
+    Pt1,
+    Pt2,
+    Total,
+}
+
+impl Stage {
+    /// The stages that are measured when running the given parts
+    fn measured(parts: &[Part]) -> Vec<Stage> {
+        let mut stages = vec![Stage::Parse];
+        if parts.contains(&Part::One) {
+            stages.push(Stage::Pt1);
+        }
+        if parts.contains(&Part::Two) {
+            stages.push(Stage::Pt2);
+        }
+        stages.push(Stage::Total);
+        stages
+    }
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Stage::Parse => "parse",
+            Stage::Pt1 => "pt1",
+            Stage::Pt2 => "pt2",
+            Stage::Total => "total",
+        })
+    }
+}
+
+impl std::str::FromStr for Stage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "parse" => Ok(Stage::Parse),
+            "pt1" => Ok(Stage::Pt1),
+            "pt2" => Ok(Stage::Pt2),
+            "total" => Ok(Stage::Total),
+            _ => Err(format!("unknown stage {s:?}")),
+        }
+    }
+}
+
+impl std::ops::AddAssign for StageTimes {
+    fn add_assign(&mut self, rhs: Self) {
+        self.parse += rhs.parse;
+        self.pt1 += rhs.pt1;
+        self.pt2 += rhs.pt2;
+    }
+}
+
+fn run_day(
+    day: usize,
+    aoc: &dyn Aoc,
+    source: &InputSource,
+    parts: &[Part],
+    record: bool,
+) -> Result<TimedSolution, AocError> {
+    let input = source.read(day)?;
+    let mut answers = load_answers(day, source)?;
+    let mut solution =
+        run_with_timing(aoc, &input, &answers.params, parts).map_err(|e| e.for_day(day))?;
+
+    solution.verify(&mut answers, record);
+    if record && let Some(path) = source.answers_path(day) {
+        answers
+            .save(&path)
+            .map_err(|source| AocError::input(day, Some(path), source))?;
+    }
+
+    Ok(solution)
+}
+
+/// The known answers and parameters for the input. Answers are stored next to the input, so there
+/// are none for stdin.
+fn load_answers(day: usize, source: &InputSource) -> Result<Answers, AocError> {
+    match source.answers_path(day) {
+        Some(path) => {
+            Answers::load(&path).map_err(|source| AocError::input(day, Some(path), source))
+        }
+        None => Ok(Answers::default()),
+    }
+}
+
+fn run_with_timing(
+    aoc: &dyn Aoc,
+    input: &str,
+    params: &Params,
+    parts: &[Part],
+) -> Result<TimedSolution, AocError> {
+    let tracker = Tracker::start();
     let start = Instant::now();
-    let (pt1, pt2) = aoc.run();
-    let elapsed = Instant::now() - start;
+    let parsed = {
+        let _span = trace::span("parse");
+        aoc.parse_input(input, params)?
+    };
+    let parse = start.elapsed();
+
+    let mut solution = TimedSolution {
+        parse,
+        pt1: None,
+        pt2: None,
+        usage: StageUsage {
+            parse: tracker.finish(),
+            ..Default::default()
+        },
+    };
+
+    for part in parts {
+        let tracker = Tracker::start();
+        let start = Instant::now();
+        let answer = {
+            let _span = trace::span(match part {
+                Part::One => "pt1",
+                Part::Two => "pt2",
+            });
+            aoc.solve_part(parsed.as_ref(), *part)?
+        };
+        let elapsed = start.elapsed();
+        let usage = tracker.finish();
+
+        let timed_answer = Some(TimedAnswer {
+            answer,
+            elapsed,
+            verification: Verification::Unknown,
+        });
+        match part {
+            Part::One => {
+                solution.pt1 = timed_answer;
+                solution.usage.pt1 = usage;
+            }
+            Part::Two => {
+                solution.pt2 = timed_answer;
+                solution.usage.pt2 = usage;
+            }
+        }
+    }
 
-    TimedSolution { elapsed, pt1, pt2 }
+    Ok(solution)
 }
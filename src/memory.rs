@@ -0,0 +1,202 @@
+//! A global allocator that counts allocations, so that the memory used by each stage can be
+//! reported alongside its time.
+//!
+//! Counting is off unless [`enable`] is called, and until then the allocator only checks a flag
+//! before passing each call on to the system allocator. The counts are shared by every thread, so
+//! allocations made by parallel jobs are included, but measuring two things at once on different
+//! threads would mix up their counts.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+};
+
+use crate::Stage;
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+/// Bytes currently allocated. Memory allocated before counting started can be freed afterwards,
+/// so this can be negative, but differences between readings are still right.
+static LIVE: AtomicI64 = AtomicI64::new(0);
+/// The most bytes allocated at once since the last [`Tracker`] started
+static PEAK: AtomicI64 = AtomicI64::new(0);
+
+/// Start counting allocations, on every thread
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+struct Counting;
+
+impl Counting {
+    #[inline]
+    fn record(&self, allocated: usize, freed: usize) {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        if allocated > 0 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            BYTES.fetch_add(allocated as u64, Ordering::Relaxed);
+        }
+        let change = allocated as i64 - freed as i64;
+        let live = LIVE.fetch_add(change, Ordering::Relaxed) + change;
+        PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            self.record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            self.record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        self.record(0, layout.size());
+    }
+
+    /// Counted as a new allocation of the new size, since that's what it costs when the memory
+    /// can't be grown in place
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            self.record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// The allocations made while something ran
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub allocations: u64,
+    /// Total size of all the allocations, including any that were freed
+    pub bytes: u64,
+    /// The most bytes that were allocated at once, on top of what was allocated at the start
+    pub peak: u64,
+    /// Bytes allocated at the end on top of what was allocated at the start, e.g. the parsed input
+    pub retained: i64,
+}
+
+/// Measures the allocations made from when it starts until it finishes
+pub struct Tracker {
+    allocations: u64,
+    bytes: u64,
+    live: i64,
+}
+
+impl Tracker {
+    pub fn start() -> Self {
+        let live = LIVE.load(Ordering::Relaxed);
+        PEAK.store(live, Ordering::Relaxed);
+        Tracker {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            bytes: BYTES.load(Ordering::Relaxed),
+            live,
+        }
+    }
+
+    pub fn finish(self) -> Usage {
+        let live = LIVE.load(Ordering::Relaxed);
+        Usage {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed) - self.allocations,
+            bytes: BYTES.load(Ordering::Relaxed) - self.bytes,
+            peak: (PEAK.load(Ordering::Relaxed) - self.live).max(0) as u64,
+            retained: live - self.live,
+        }
+    }
+}
+
+/// The allocations made by each stage of a solution
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageUsage {
+    pub parse: Usage,
+    pub pt1: Usage,
+    pub pt2: Usage,
+}
+
+impl StageUsage {
+    /// The usage of a stage, where the total's peak includes what earlier stages kept allocated
+    pub fn get(&self, stage: Stage) -> Usage {
+        match stage {
+            Stage::Parse => self.parse,
+            Stage::Pt1 => self.pt1,
+            Stage::Pt2 => self.pt2,
+            Stage::Total => {
+                let mut total = Usage::default();
+                for usage in [self.parse, self.pt1, self.pt2] {
+                    let peak = (total.retained + usage.peak as i64).max(0) as u64;
+                    total.allocations += usage.allocations;
+                    total.bytes += usage.bytes;
+                    total.peak = total.peak.max(peak);
+                    total.retained += usage.retained;
+                }
+                total
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_usage() {
+        let usage = StageUsage {
+            parse: Usage {
+                allocations: 3,
+                bytes: 1000,
+                peak: 800,
+                retained: 500,
+            },
+            pt1: Usage {
+                allocations: 2,
+                bytes: 400,
+                peak: 400,
+                retained: 0,
+            },
+            pt2: Usage {
+                allocations: 1,
+                bytes: 100,
+                peak: 100,
+                retained: 8,
+            },
+        };
+        let expected = Usage {
+            allocations: 6,
+            bytes: 1500,
+            peak: 900,
+            retained: 508,
+        };
+        assert_eq!(expected, usage.get(Stage::Total));
+    }
+
+    #[test]
+    fn test_tracker() {
+        enable();
+        let tracker = Tracker::start();
+        let mut v: Vec<u64> = Vec::with_capacity(1000);
+        v.push(1);
+        drop(v);
+        let usage = tracker.finish();
+        // Other tests may be allocating at the same time, so only check what this test did
+        assert!(usage.allocations >= 1);
+        assert!(usage.bytes >= 8000);
+    }
+}
@@ -0,0 +1,24 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rayon::ThreadPoolBuildError;
+
+/// Whether solvers should use their rayon-backed implementations
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Size the global rayon pool to `threads` (or one per CPU if 0) and enable the parallel
+/// implementations of the solvers that have them, unless the pool only has one thread.
+///
+/// This must be called before anything else uses rayon.
+pub fn configure(threads: usize) -> Result<(), ThreadPoolBuildError> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()?;
+    ENABLED.store(rayon::current_num_threads() > 1, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Whether solvers should use their parallel implementations. This is off unless `--threads` is
+/// given, so that timings are for the single threaded implementations by default.
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
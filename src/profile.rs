@@ -0,0 +1,291 @@
+use std::{
+    fs,
+    path::PathBuf,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Aoc, DayArgs, Part,
+    answers::Params,
+    bench::parse_seconds,
+    error::AocError,
+    flamegraph::Stacks,
+    load_answers, run_with_timing,
+    trace::{self, Span},
+};
+
+#[derive(clap::Args)]
+#[command(mut_arg("day", |day| day.required(true).help("The day to run")))]
+pub struct ProfileArgs {
+    #[command(flatten)]
+    pub days: DayArgs,
+
+    /// How long to run the day for, in seconds
+    #[arg(short, long, default_value = "5", value_parser = parse_seconds)]
+    time: Duration,
+
+    /// Where to write the profile: an SVG flamegraph if the name ends in `.svg`, otherwise folded
+    /// stacks (one `frame;frame;frame count` line per stack) for other flamegraph tools
+    #[arg(short, long, default_value = "flamegraph.svg")]
+    output: PathBuf,
+
+    /// How many times per second to sample the call stack
+    #[arg(long, default_value_t = 997, value_parser = clap::value_parser!(i32).range(1..))]
+    frequency: i32,
+
+    /// Only time the parse, pt1 and pt2 stages, and the spans inside the solver, instead of
+    /// sampling the call stack. This is the only option on platforms without sampling.
+    #[arg(long)]
+    spans: bool,
+}
+
+/// The stacks from profiling a day, and what their weights measure
+struct Profile {
+    stacks: Stacks,
+    unit: &'static str,
+    runs: usize,
+}
+
+pub fn run(args: &ProfileArgs) -> ExitCode {
+    let (day, aoc) = match args.days.days() {
+        Ok(days) => days[0],
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let source = args.days.source();
+    let input = match source.read(day) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let params = match load_answers(day, &source) {
+        Ok(answers) => answers.params,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let parts = args.days.parts();
+
+    // One run to find any errors before profiling, which also warms up
+    if let Err(e) = run_with_timing(aoc, &input, &params, &parts) {
+        eprintln!("error: {}", e.for_day(day));
+        return ExitCode::FAILURE;
+    }
+
+    let root = format!("day{day:02}");
+    let time_budget = args.time;
+    let profile = if args.spans || !sampling::AVAILABLE {
+        profile_spans(&root, aoc, &input, &params, &parts, time_budget)
+    } else {
+        match sampling::profile(
+            &root,
+            aoc,
+            &input,
+            &params,
+            &parts,
+            time_budget,
+            args.frequency,
+        ) {
+            Ok(profile) => profile,
+            Err(e) => {
+                eprintln!("warning: could not sample the call stack ({e}), timing stages instead");
+                profile_spans(&root, aoc, &input, &params, &parts, time_budget)
+            }
+        }
+    };
+
+    let contents = if args.output.extension().is_some_and(|ext| ext == "svg") {
+        let title = format!("day {day} ({} runs)", profile.runs);
+        crate::flamegraph::svg(&profile.stacks, &title, profile.unit)
+    } else {
+        crate::flamegraph::folded(&profile.stacks)
+    };
+    if let Err(e) = fs::write(&args.output, contents) {
+        eprintln!("error: could not write {}: {e}", args.output.display());
+        return ExitCode::FAILURE;
+    }
+
+    let total: u64 = profile.stacks.values().sum();
+    println!(
+        "{} runs, {total} {} written to {}",
+        profile.runs,
+        profile.unit,
+        args.output.display()
+    );
+    ExitCode::SUCCESS
+}
+
+/// Run the day over and over until the time budget is used up, calling `after_run` after each run.
+/// Sampled stacks are cut off at this function, so it must not be inlined.
+#[inline(never)]
+fn run_for(
+    aoc: &dyn Aoc,
+    input: &str,
+    params: &Params,
+    parts: &[Part],
+    time_budget: Duration,
+    mut after_run: impl FnMut(),
+) -> Result<usize, AocError> {
+    let mut runs = 0;
+    let start = Instant::now();
+    while runs == 0 || start.elapsed() < time_budget {
+        run_with_timing(aoc, input, params, parts)?;
+        after_run();
+        runs += 1;
+    }
+    Ok(runs)
+}
+
+/// Profile using the spans recorded by [`trace`], with each stack weighted by the time spent in that
+/// span but not in the spans inside it
+fn profile_spans(
+    root: &str,
+    aoc: &dyn Aoc,
+    input: &str,
+    params: &Params,
+    parts: &[Part],
+    time_budget: Duration,
+) -> Profile {
+    trace::enable();
+    trace::take();
+    let mut spans = Vec::new();
+    let runs = run_for(aoc, input, params, parts, time_budget, || {
+        trace::merge(&mut spans, trace::take())
+    })
+    .expect("failed after the warm up run succeeded");
+
+    let mut stacks = Stacks::new();
+    add_stacks(&mut stacks, &mut vec![root.to_string()], &spans);
+    Profile {
+        stacks,
+        unit: "ns",
+        runs,
+    }
+}
+
+/// Add a stack for each span, and the spans inside it, under the frames in `path`
+fn add_stacks(stacks: &mut Stacks, path: &mut Vec<String>, spans: &[Span]) {
+    for span in spans {
+        path.push(span.name.to_string());
+        let self_time = span.self_time().as_nanos() as u64;
+        if self_time > 0 {
+            stacks.insert(path.clone(), self_time);
+        }
+        add_stacks(stacks, path, &span.children);
+        path.pop();
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod sampling {
+    use std::time::Duration;
+
+    use super::{Profile, run_for};
+    use crate::{Aoc, Part, answers::Params, flamegraph::Stacks};
+
+    pub const AVAILABLE: bool = true;
+
+    /// Profile by sampling the call stack of every thread, with stacks inside [`run_for`] rooted at
+    /// `root` and other threads' stacks rooted at the thread's name
+    pub fn profile(
+        root: &str,
+        aoc: &dyn Aoc,
+        input: &str,
+        params: &Params,
+        parts: &[Part],
+        time_budget: Duration,
+        frequency: i32,
+    ) -> Result<Profile, pprof::Error> {
+        let guard = pprof::ProfilerGuardBuilder::default()
+            .frequency(frequency)
+            .build()?;
+        let runs = run_for(aoc, input, params, parts, time_budget, || {})
+            .expect("failed after the warm up run succeeded");
+        let report = guard.report().build()?;
+
+        let mut stacks = Stacks::new();
+        for (frames, &count) in &report.data {
+            // Frames are listed from the leaf up, and so are the functions inlined into each one
+            let names: Vec<String> = frames
+                .frames
+                .iter()
+                .rev()
+                .flat_map(|symbols| symbols.iter().rev().map(|symbol| symbol.name()))
+                .collect();
+            let stack = match names.iter().position(|name| is_run_for(name)) {
+                Some(i) => std::iter::once(root.to_string())
+                    .chain(names[i + 1..].iter().cloned())
+                    .collect(),
+                None => std::iter::once(frames.thread_name_or_id())
+                    .chain(names)
+                    .collect(),
+            };
+            *stacks.entry(stack).or_default() += count as u64;
+        }
+
+        Ok(Profile {
+            stacks,
+            unit: "samples",
+            runs,
+        })
+    }
+
+    fn is_run_for(name: &str) -> bool {
+        name.contains("profile::run_for")
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+mod sampling {
+    use std::time::Duration;
+
+    use super::Profile;
+    use crate::{Aoc, Part, answers::Params};
+
+    pub const AVAILABLE: bool = false;
+
+    pub fn profile(
+        _root: &str,
+        _aoc: &dyn Aoc,
+        _input: &str,
+        _params: &Params,
+        _parts: &[Part],
+        _time_budget: Duration,
+        _frequency: i32,
+    ) -> Result<Profile, String> {
+        unreachable!("sampling is not available on this platform")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::REGISTRY;
+
+    #[test]
+    fn test_profile_spans() {
+        let aoc = REGISTRY.select(crate::DEFAULT_YEAR, Some(8), None).unwrap()[0].1;
+        let input = fs::read_to_string("inputs/08.ex").unwrap();
+        let profile = profile_spans(
+            "day08",
+            aoc,
+            &input,
+            &Params::default(),
+            &[Part::Two],
+            Duration::ZERO,
+        );
+        assert_eq!(1, profile.runs);
+        assert!(profile.stacks.keys().all(|stack| stack[0] == "day08"));
+        assert!(profile.stacks.contains_key(&vec![
+            "day08".to_string(),
+            "parse".to_string(),
+            "sort".to_string()
+        ]));
+    }
+}
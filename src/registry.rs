@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+
+use crate::{Aoc, error::AocError, generate::Rng, validate::Violation, visualize::Visualization};
+
+/// Name of the solver that each day registers, which is used unless another is asked for
+pub const DEFAULT_SOLVER: &str = "default";
+
+/// The solvers for each year and day. Each day has a default solver, and can have alternatives
+/// (e.g. a naive version to check the optimised one against), a generator of random inputs, a
+/// validator of inputs and a visualizer.
+#[derive(Default)]
+pub struct Registry {
+    solvers: BTreeMap<(u16, usize), Vec<Solver>>,
+    generators: BTreeMap<(u16, usize), Generator>,
+    validators: BTreeMap<(u16, usize), Validator>,
+    visualizers: BTreeMap<(u16, usize), Visualizer>,
+}
+
+/// Makes a random input for a day with roughly `size` items, such as lines or points
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+/// Checks an input for a day, finding everything that the parser wouldn't accept or that breaks an
+/// assumption the solver makes
+pub type Validator = fn(&str) -> Vec<Violation>;
+
+/// Draws how a day solves an input
+pub type Visualizer = fn(&str) -> Result<Visualization, AocError>;
+
+#[derive(Clone, Copy)]
+pub struct Solver {
+    pub name: &'static str,
+    pub aoc: &'static (dyn Aoc + Send + Sync),
+}
+
+impl Registry {
+    pub fn register(
+        &mut self,
+        year: u16,
+        day: usize,
+        name: &'static str,
+        aoc: &'static (dyn Aoc + Send + Sync),
+    ) {
+        let solvers = self.solvers.entry((year, day)).or_default();
+        assert!(
+            solvers.iter().all(|solver| solver.name != name),
+            "day {day} of {year} has two solvers named {name:?}"
+        );
+        solvers.push(Solver { name, aoc });
+    }
+
+    pub fn register_generator(&mut self, year: u16, day: usize, generator: Generator) {
+        self.generators.insert((year, day), generator);
+    }
+
+    pub fn register_validator(&mut self, year: u16, day: usize, validator: Validator) {
+        self.validators.insert((year, day), validator);
+    }
+
+    pub fn register_visualizer(&mut self, year: u16, day: usize, visualizer: Visualizer) {
+        self.visualizers.insert((year, day), visualizer);
+    }
+
+    /// The days of `year` that have solvers, in order
+    pub fn days(&self, year: u16) -> Vec<usize> {
+        self.solvers
+            .keys()
+            .filter(|(y, _)| *y == year)
+            .map(|(_, day)| *day)
+            .collect()
+    }
+
+    pub fn years(&self) -> Vec<u16> {
+        let mut years: Vec<u16> = self.solvers.keys().map(|(year, _)| *year).collect();
+        years.dedup();
+        years
+    }
+
+    /// The solver for the given day and year, where `name` picks an alternative solver instead of
+    /// the default
+    pub fn solver(&self, year: u16, day: usize, name: Option<&str>) -> Result<Solver, String> {
+        let solvers = self.solvers.get(&(year, day)).ok_or_else(|| {
+            if self.days(year).is_empty() {
+                self.no_such_year(year)
+            } else {
+                format!(
+                    "there is no solution for day {day} of {year}, available days are {}",
+                    join(&self.days(year))
+                )
+            }
+        })?;
+
+        let name = name.unwrap_or(DEFAULT_SOLVER);
+        solvers
+            .iter()
+            .find(|solver| solver.name == name)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = solvers.iter().map(|solver| solver.name).collect();
+                format!(
+                    "day {day} of {year} has no solver named {name:?}, available solvers are {}",
+                    join(&names)
+                )
+            })
+    }
+
+    /// The input generator for the given day and year
+    pub fn generator(&self, year: u16, day: usize) -> Result<Generator, String> {
+        lookup(&self.generators, year, day, "input generator", "generators")
+    }
+
+    /// The input validator for the given day and year
+    pub fn validator(&self, year: u16, day: usize) -> Result<Validator, String> {
+        lookup(&self.validators, year, day, "input validator", "validators")
+    }
+
+    /// The visualizer for the given day and year
+    pub fn visualizer(&self, year: u16, day: usize) -> Result<Visualizer, String> {
+        lookup(&self.visualizers, year, day, "visualizer", "visualizers")
+    }
+
+    /// The selected days of `year` with their solvers: either one day, or every day with its
+    /// default solver
+    pub fn select(
+        &self,
+        year: u16,
+        day: Option<usize>,
+        name: Option<&str>,
+    ) -> Result<Vec<(usize, &'static (dyn Aoc + Send + Sync))>, String> {
+        if let Some(day) = day {
+            return Ok(vec![(day, self.solver(year, day, name)?.aoc)]);
+        }
+
+        let days = self.days(year);
+        if days.is_empty() {
+            return Err(self.no_such_year(year));
+        }
+        days.into_iter()
+            .map(|day| Ok((day, self.solver(year, day, None)?.aoc)))
+            .collect()
+    }
+
+    fn no_such_year(&self, year: u16) -> String {
+        format!(
+            "there are no solutions for {year}, available years are {}",
+            join(&self.years())
+        )
+    }
+}
+
+/// The entry for the day, or a description of which days have one
+fn lookup<T: Copy>(
+    map: &BTreeMap<(u16, usize), T>,
+    year: u16,
+    day: usize,
+    what: &str,
+    plural: &str,
+) -> Result<T, String> {
+    map.get(&(year, day)).copied().ok_or_else(|| {
+        let days: Vec<usize> = map
+            .keys()
+            .filter(|(y, _)| *y == year)
+            .map(|(_, day)| *day)
+            .collect();
+        if days.is_empty() {
+            format!("there are no {what}s for {year}")
+        } else {
+            format!(
+                "day {day} of {year} has no {what}, days with {plural} are {}",
+                join(&days)
+            )
+        }
+    })
+}
+
+fn join(items: &[impl ToString]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Answer, Solution, error::AocError};
+
+    struct Fixed(u64);
+
+    impl Solution for Fixed {
+        type Parsed = ();
+
+        fn parse(&self, _input: &str) -> Result<Self::Parsed, AocError> {
+            Ok(())
+        }
+
+        fn part1(&self, _parsed: &()) -> Result<Answer, AocError> {
+            Ok(self.0.into())
+        }
+
+        fn part2(&self, _parsed: &()) -> Result<Answer, AocError> {
+            Ok(self.0.into())
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::default();
+        registry.register(2025, 1, DEFAULT_SOLVER, &Fixed(1));
+        registry.register(2025, 1, "naive", &Fixed(2));
+        registry.register(2025, 2, DEFAULT_SOLVER, &Fixed(3));
+        registry.register(2024, 5, DEFAULT_SOLVER, &Fixed(4));
+        registry.register_generator(2025, 2, |_, size| "x".repeat(size));
+        registry
+    }
+
+    #[test]
+    fn test_select() {
+        let registry = registry();
+        assert_eq!(vec![1, 2], registry.days(2025));
+        assert_eq!(vec![2024, 2025], registry.years());
+        assert_eq!(
+            "naive",
+            registry.solver(2025, 1, Some("naive")).unwrap().name
+        );
+        assert_eq!(2, registry.select(2025, None, None).unwrap().len());
+    }
+
+    #[test]
+    fn test_errors() {
+        let registry = registry();
+        // The solvers aren't Debug, so use `err` rather than `unwrap_err`
+        assert_eq!(
+            Some("there is no solution for day 13 of 2025, available days are 1, 2".to_string()),
+            registry.select(2025, Some(13), None).err()
+        );
+        assert_eq!(
+            Some("there are no solutions for 2015, available years are 2024, 2025".to_string()),
+            registry.select(2015, None, None).err()
+        );
+        assert_eq!(
+            Some(
+                "day 1 of 2025 has no solver named \"fast\", available solvers are default, naive"
+                    .to_string()
+            ),
+            registry.select(2025, Some(1), Some("fast")).err()
+        );
+        assert_eq!(
+            Some("day 1 of 2025 has no input generator, days with generators are 2".to_string()),
+            registry.generator(2025, 1).err()
+        );
+    }
+}
@@ -0,0 +1,449 @@
+use std::{fmt::Write, time::Duration};
+
+use clap::ValueEnum;
+
+use crate::{
+    StageTimes, TimedAnswer, TimedSolution, answers::Verification, error::AocError, memory::Usage,
+};
+
+/// How the results of a run are printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Fixed-width table for reading in a terminal
+    #[default]
+    Text,
+    /// An array with one object per day
+    Json,
+    /// One row per day, with a header row
+    Csv,
+    /// A table that can be pasted into a README
+    Markdown,
+}
+
+/// Formats the results of a run one day at a time, so that each day can be printed as soon as it
+/// finishes. Each piece of output includes its own line endings.
+pub struct Report {
+    format: Format,
+    /// Whether several days are being run, rather than a single day
+    all_days: bool,
+    /// Whether to include the allocations made by each stage
+    allocs: bool,
+    rows: usize,
+}
+
+impl Report {
+    pub fn new(format: Format, all_days: bool, allocs: bool) -> Self {
+        Self {
+            format,
+            all_days,
+            allocs,
+            rows: 0,
+        }
+    }
+
+    /// Output before the first day, if any
+    pub fn header(&self) -> Option<String> {
+        match self.format {
+            Format::Text => None,
+            Format::Json => Some("[".to_string()),
+            Format::Csv => {
+                let mut header =
+                    "day,pt1,pt1_status,pt2,pt2_status,parse_us,pt1_us,pt2_us,total_us,error"
+                        .to_string();
+                if self.allocs {
+                    for stage in STAGES {
+                        write!(
+                            header,
+                            ",{stage}_allocs,{stage}_alloc_bytes,{stage}_peak_bytes"
+                        )
+                        .unwrap();
+                    }
+                }
+                Some(header + "\n")
+            }
+            Format::Markdown => {
+                let mut header = "| Day | Part 1 | | Part 2 | | Parse (us) | Part 1 (us) | \
+                                  Part 2 (us) | Total (us) |"
+                    .to_string();
+                let mut divider = "|---:|---:|---|---:|---|---:|---:|---:|---:|".to_string();
+                if self.allocs {
+                    header += " Parse allocs | Part 1 allocs | Part 2 allocs |";
+                    divider += "---:|---:|---:|";
+                }
+                Some(format!("{header}\n{divider}\n"))
+            }
+        }
+    }
+
+    /// Output for one day. When running a single day as text, errors are left for the caller to
+    /// report on stderr, so there is no output.
+    pub fn row(&mut self, day: usize, result: &Result<TimedSolution, AocError>) -> Option<String> {
+        let allocs = self.allocs;
+        let row = match (self.format, result) {
+            (Format::Text, Ok(solution)) => {
+                let prefix = if self.all_days {
+                    format!("day {day:2}: ")
+                } else {
+                    String::new()
+                };
+                let mut row = format!("{prefix}{solution}\n");
+                if allocs {
+                    let usage = stage_usage(solution).map(|(stage, usage)| {
+                        usage.map_or(format!("{stage} = -"), |usage| {
+                            format!(
+                                "{stage} = {} ({} bytes, peak {} bytes)",
+                                usage.allocations, usage.bytes, usage.peak
+                            )
+                        })
+                    });
+                    let indent = " ".repeat(prefix.len());
+                    writeln!(row, "{indent}allocs: {}", usage.join(", ")).unwrap();
+                }
+                row
+            }
+            (Format::Text, Err(e)) if self.all_days => {
+                format!("day {day:2}: error: {}\n", e.kind())
+            }
+            (Format::Text, Err(_)) => return None,
+            (Format::Json, result) => {
+                let separator = if self.rows == 0 { "" } else { "," };
+                format!("{separator}\n  {}", json_row(day, result, allocs))
+            }
+            (Format::Csv, result) => csv_row(day, result, allocs) + "\n",
+            (Format::Markdown, result) => markdown_row(day, result, allocs) + "\n",
+        };
+
+        self.rows += 1;
+        Some(row)
+    }
+
+    /// Output after the last day, if any. `total` is the time summed across all days, and
+    /// `wall_clock` is the time taken to run them if they ran concurrently.
+    pub fn footer(&self, total: StageTimes, wall_clock: Option<Duration>) -> Option<String> {
+        match self.format {
+            Format::Text if self.all_days => {
+                // Days that ran concurrently overlap, so their times only add up to CPU time
+                let label = match wall_clock {
+                    Some(_) => "summed CPU time",
+                    None => "total elapsed time",
+                };
+                let mut footer = format!(
+                    "{label}: {} us (parse = {} us, pt1 = {} us, pt2 = {} us)\n",
+                    total.total().as_micros(),
+                    total.parse.as_micros(),
+                    total.pt1.as_micros(),
+                    total.pt2.as_micros()
+                );
+                if let Some(wall_clock) = wall_clock {
+                    writeln!(footer, "wall-clock time: {} us", wall_clock.as_micros()).unwrap();
+                }
+                Some(footer)
+            }
+            Format::Json if self.rows == 0 => Some("]\n".to_string()),
+            Format::Json => Some("\n]\n".to_string()),
+            Format::Markdown if self.all_days => {
+                let allocs = if self.allocs { " | | |" } else { "" };
+                let mut footer = format!(
+                    "| **Total** | | | | | {} | {} | {} | {} |{allocs}\n",
+                    total.parse.as_micros(),
+                    total.pt1.as_micros(),
+                    total.pt2.as_micros(),
+                    total.total().as_micros()
+                );
+                if let Some(wall_clock) = wall_clock {
+                    writeln!(
+                        footer,
+                        "| **Wall-clock** | | | | | | | | {} |{allocs}",
+                        wall_clock.as_micros()
+                    )
+                    .unwrap();
+                }
+                Some(footer)
+            }
+            Format::Text | Format::Csv | Format::Markdown => None,
+        }
+    }
+}
+
+/// The names of the stages with allocations, as used in the output
+const STAGES: [&str; 3] = ["parse", "pt1", "pt2"];
+
+/// The allocations made by each stage, or None for a part that wasn't run
+fn stage_usage(solution: &TimedSolution) -> [(&'static str, Option<Usage>); 3] {
+    let usage = &solution.usage;
+    let [parse, pt1, pt2] = STAGES;
+    [
+        (parse, Some(usage.parse)),
+        (pt1, solution.pt1.as_ref().map(|_| usage.pt1)),
+        (pt2, solution.pt2.as_ref().map(|_| usage.pt2)),
+    ]
+}
+
+fn json_row(day: usize, result: &Result<TimedSolution, AocError>, allocs: bool) -> String {
+    let solution = match result {
+        Ok(solution) => solution,
+        Err(e) => {
+            return format!(
+                r#"{{"day": {day}, "error": {}}}"#,
+                json_string(&e.kind().to_string())
+            );
+        }
+    };
+
+    let part = |a: &Option<TimedAnswer>| match a {
+        Some(a) => {
+            let mut json = format!(
+                r#"{{"answer": {}, "status": "{}", "elapsed_us": {}"#,
+                json_string(&a.answer.to_string()),
+                a.verification.status(),
+                a.elapsed.as_micros()
+            );
+            if let Verification::Fail { expected } = &a.verification {
+                write!(json, r#", "expected": {}"#, json_string(expected)).unwrap();
+            }
+            json.push('}');
+            json
+        }
+        None => "null".to_string(),
+    };
+
+    let mut json = format!(
+        r#"{{"day": {day}, "pt1": {}, "pt2": {}, "parse_us": {}, "total_us": {}"#,
+        part(&solution.pt1),
+        part(&solution.pt2),
+        solution.parse.as_micros(),
+        solution.times().total().as_micros()
+    );
+    if allocs {
+        let stages = stage_usage(solution).map(|(stage, usage)| {
+            let usage = usage.map_or("null".to_string(), |usage| {
+                format!(
+                    r#"{{"allocations": {}, "bytes": {}, "peak_bytes": {}}}"#,
+                    usage.allocations, usage.bytes, usage.peak
+                )
+            });
+            format!(r#""{stage}": {usage}"#)
+        });
+        write!(json, r#", "allocs": {{{}}}"#, stages.join(", ")).unwrap();
+    }
+    json.push('}');
+    json
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// The answer, status and elapsed microseconds for a part, or empty fields if it wasn't run
+fn part_fields(a: &Option<TimedAnswer>) -> [String; 3] {
+    match a {
+        Some(a) => [
+            a.answer.to_string(),
+            a.verification.status().to_string(),
+            a.elapsed.as_micros().to_string(),
+        ],
+        None => Default::default(),
+    }
+}
+
+fn csv_row(day: usize, result: &Result<TimedSolution, AocError>, allocs: bool) -> String {
+    let usage = match result {
+        Ok(solution) if allocs => stage_usage(solution)
+            .into_iter()
+            .flat_map(|(_, usage)| match usage {
+                Some(usage) => [usage.allocations, usage.bytes, usage.peak].map(|n| n.to_string()),
+                None => Default::default(),
+            })
+            .collect(),
+        Err(_) if allocs => vec![String::new(); 9],
+        _ => Vec::new(),
+    };
+    let fields = match result {
+        Ok(solution) => {
+            let [pt1, pt1_status, pt1_us] = part_fields(&solution.pt1);
+            let [pt2, pt2_status, pt2_us] = part_fields(&solution.pt2);
+            [
+                day.to_string(),
+                pt1,
+                pt1_status,
+                pt2,
+                pt2_status,
+                solution.parse.as_micros().to_string(),
+                pt1_us,
+                pt2_us,
+                solution.times().total().as_micros().to_string(),
+                String::new(),
+            ]
+        }
+        Err(e) => {
+            let mut fields: [String; 10] = Default::default();
+            fields[0] = day.to_string();
+            fields[9] = e.kind().to_string();
+            fields
+        }
+    };
+
+    fields
+        .into_iter()
+        .chain(usage)
+        .map(|field| csv_field(&field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn markdown_row(day: usize, result: &Result<TimedSolution, AocError>, allocs: bool) -> String {
+    let usage = match result {
+        Ok(solution) if allocs => stage_usage(solution)
+            .into_iter()
+            .map(|(_, usage)| match usage {
+                Some(usage) => format!(
+                    "{} ({} B, peak {} B)",
+                    usage.allocations, usage.bytes, usage.peak
+                ),
+                None => "-".to_string(),
+            })
+            .collect(),
+        Err(_) if allocs => vec![String::new(); 3],
+        _ => Vec::new(),
+    };
+    let cells = match result {
+        Ok(solution) => {
+            let [pt1, pt1_status, pt1_us] = part_fields(&solution.pt1);
+            let [pt2, pt2_status, pt2_us] = part_fields(&solution.pt2);
+            let or_dash = |s: String| if s.is_empty() { "-".to_string() } else { s };
+            [
+                day.to_string(),
+                or_dash(pt1),
+                pt1_status,
+                or_dash(pt2),
+                pt2_status,
+                solution.parse.as_micros().to_string(),
+                or_dash(pt1_us),
+                or_dash(pt2_us),
+                solution.times().total().as_micros().to_string(),
+            ]
+        }
+        Err(e) => {
+            let mut cells: [String; 9] = Default::default();
+            cells[0] = day.to_string();
+            cells[1] = format!("error: {}", e.kind());
+            cells
+        }
+    };
+
+    let cells: Vec<String> = cells
+        .into_iter()
+        .chain(usage)
+        .map(|cell| cell.replace('|', "\\|").replace('\n', " "))
+        .collect();
+    format!("| {} |", cells.join(" | "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Answer;
+
+    fn solution() -> TimedSolution {
+        TimedSolution {
+            parse: Duration::from_micros(10),
+            pt1: Some(TimedAnswer {
+                answer: Answer::UInt(42),
+                elapsed: Duration::from_micros(5),
+                verification: Verification::Pass,
+            }),
+            pt2: None,
+            usage: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_json() {
+        let mut report = Report::new(Format::Json, true, false);
+        let output = [
+            report.header(),
+            report.row(1, &Ok(solution())),
+            report.row(2, &Err(AocError::unsolvable("no \"loop\""))),
+            report.footer(StageTimes::default(), None),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<String>();
+        assert_eq!(
+            concat!(
+                "[\n",
+                r#"  {"day": 1, "pt1": {"answer": "42", "status": "pass", "elapsed_us": 5}, "pt2": null, "parse_us": 10, "total_us": 15},"#,
+                "\n",
+                r#"  {"day": 2, "error": "no solution: no \"loop\""}"#,
+                "\n]\n"
+            ),
+            output
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut report = Report::new(Format::Csv, true, false);
+        assert_eq!(
+            Some("1,42,pass,,,10,5,,15,\n".to_string()),
+            report.row(1, &Ok(solution()))
+        );
+        assert_eq!(
+            Some("2,,,,,,,,,\"no solution: a, b\"\n".to_string()),
+            report.row(2, &Err(AocError::unsolvable("a, b")))
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        let mut report = Report::new(Format::Markdown, true, false);
+        assert_eq!(
+            Some("| 1 | 42 | pass | - |  | 10 | 5 | - | 15 |\n".to_string()),
+            report.row(1, &Ok(solution()))
+        );
+    }
+
+    #[test]
+    fn test_allocs() {
+        let mut allocating = solution();
+        allocating.usage.parse = Usage {
+            allocations: 3,
+            bytes: 100,
+            peak: 60,
+            retained: 40,
+        };
+        let mut report = Report::new(Format::Csv, true, true);
+        assert!(report.header().unwrap().ends_with(",pt2_peak_bytes\n"));
+        assert_eq!(
+            Some("1,42,pass,,,10,5,,15,,3,100,60,0,0,0,,,\n".to_string()),
+            report.row(1, &Ok(allocating))
+        );
+
+        let mut report = Report::new(Format::Text, false, true);
+        assert!(report.row(1, &Ok(solution())).unwrap().ends_with(
+            "\nallocs: parse = 0 (0 bytes, peak 0 bytes), pt1 = 0 (0 bytes, peak 0 bytes), pt2 = -\n"
+        ));
+    }
+}
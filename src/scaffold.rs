@@ -0,0 +1,173 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use crate::DEFAULT_YEAR;
+
+/// The template for a new day, where `XX` is replaced with the two digit day number
+const TEMPLATE: &str = include_str!("template.rs");
+
+#[derive(clap::Args)]
+pub struct NewArgs {
+    /// The day to create
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+
+    /// The year to create the day in. Days for years other than the default go in a `yearYYYY`
+    /// module directory, with their inputs in a subdirectory of the inputs directory.
+    #[arg(long, default_value_t = DEFAULT_YEAR)]
+    year: u16,
+
+    /// Directory to create the empty input, example and answers files in, relative to the crate
+    /// root
+    #[arg(long, default_value = "inputs")]
+    inputs_dir: PathBuf,
+}
+
+pub fn run(args: &NewArgs) -> ExitCode {
+    let number = format!("{:02}", args.day);
+    // Both directories are found from the crate root, so that the module's example path is right
+    // wherever this is run from
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut src_dir = root.join("src");
+    let mut inputs_dir = root.join(&args.inputs_dir);
+    if args.year != DEFAULT_YEAR {
+        src_dir.push(format!("year{}", args.year));
+        inputs_dir.push(args.year.to_string());
+    }
+
+    for dir in [&src_dir, &inputs_dir] {
+        if let Err(e) = fs::create_dir_all(dir) {
+            eprintln!("error: could not create {}: {e}", dir.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let example = example_path(root, &src_dir, &inputs_dir.join(format!("{number}.ex")));
+    let source = TEMPLATE
+        .replace("\"../inputs/XX.ex\"", &format!("{example:?}"))
+        .replace("XX", &number);
+
+    let module = src_dir.join(format!("day{number}.rs"));
+    match create(&module, &source) {
+        Ok(true) => println!("created {}", module.display()),
+        Ok(false) => {
+            eprintln!("error: {} already exists", module.display());
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!("error: could not create {}: {e}", module.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    // Leave any of these that already exist alone, e.g. if the input was downloaded first
+    for extension in ["ex", "ex.ans", "in", "ans"] {
+        let path = inputs_dir.join(format!("{number}.{extension}"));
+        match create(&path, "") {
+            Ok(true) => println!("created {}", path.display()),
+            Ok(false) => println!("kept existing {}", path.display()),
+            Err(e) => {
+                eprintln!("error: could not create {}: {e}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    // Days for the default year are declared in main.rs, and days for other years in their
+    // year's mod.rs, which is in turn declared in main.rs
+    let main = root.join("src").join("main.rs");
+    let mut declarations = vec![(main.clone(), format!("mod day{number};"))];
+    if args.year != DEFAULT_YEAR {
+        declarations = vec![
+            (src_dir.join("mod.rs"), format!("pub mod day{number};")),
+            (main, format!("mod year{};", args.year)),
+        ];
+    }
+    for (file, declaration) in declarations {
+        match declare(&file, &declaration) {
+            Ok(true) => println!("declared {declaration} in {}", file.display()),
+            Ok(false) => {}
+            Err(e) => {
+                eprintln!(
+                    "error: could not declare {declaration} in {}: {e}",
+                    file.display()
+                );
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    println!(
+        "day {} of {} will be registered the next time the crate is built",
+        args.day, args.year
+    );
+    ExitCode::SUCCESS
+}
+
+/// The path for `include_str!` in a module in `src_dir` to read `example` from, relative to the
+/// module if the example is inside the crate
+fn example_path(root: &Path, src_dir: &Path, example: &Path) -> String {
+    let (Ok(example), Ok(module_dir)) = (example.strip_prefix(root), src_dir.strip_prefix(root))
+    else {
+        return example.display().to_string();
+    };
+    let up = "../".repeat(module_dir.components().count());
+    format!("{up}{}", example.display())
+}
+
+/// Add `declaration` (e.g. `mod day01;`) to the `mod` items in `file` in alphabetical order,
+/// creating the file if it doesn't exist. Returns false if it was already declared.
+fn declare(file: &Path, declaration: &str) -> io::Result<bool> {
+    let source = match fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let name = |line: &str| {
+        let line = line.trim();
+        line.strip_prefix("pub ")
+            .unwrap_or(line)
+            .strip_prefix("mod ")?
+            .strip_suffix(';')
+            .map(str::to_string)
+    };
+    let new = name(declaration).expect("declaration should be a mod item");
+
+    let mut lines: Vec<&str> = source.lines().collect();
+    let mut modules = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| Some((i, name(line)?)));
+    if modules.clone().any(|(_, module)| module == new) {
+        return Ok(false);
+    }
+    let position = match modules.clone().find(|(_, module)| *module > new) {
+        // Keep any attributes with the module they belong to
+        Some((mut i, _)) => {
+            while i > 0 && lines[i - 1].trim_start().starts_with("#[") {
+                i -= 1;
+            }
+            i
+        }
+        None => modules.next_back().map_or(lines.len(), |(i, _)| i + 1),
+    };
+    lines.insert(position, declaration);
+    fs::write(file, lines.join("\n") + "\n")?;
+    Ok(true)
+}
+
+/// Create a file with the given contents, returning false if it already exists
+fn create(path: &Path, contents: &str) -> io::Result<bool> {
+    match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(mut file) => {
+            file.write_all(contents.as_bytes())?;
+            Ok(true)
+        }
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(e),
+    }
+}
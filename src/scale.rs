@@ -0,0 +1,225 @@
+use std::{
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    DayArgs, REGISTRY, Stage, StageTimes,
+    answers::Params,
+    bench::{Stats, parse_seconds},
+    generate::Rng,
+    run_with_timing,
+};
+
+#[derive(clap::Args)]
+#[command(mut_arg("day", |day| day.required(true).help("The day to run")))]
+pub struct ScaleArgs {
+    #[command(flatten)]
+    pub days: DayArgs,
+
+    /// Smallest input size to generate
+    #[arg(long, default_value_t = 100)]
+    from: usize,
+
+    /// Largest input size to generate
+    #[arg(long, default_value_t = 1600)]
+    to: usize,
+
+    /// Number of sizes to run, spaced evenly on a log scale from --from to --to
+    #[arg(long, default_value_t = 5)]
+    steps: usize,
+
+    /// Seed for the input generator
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Time budget per size in seconds
+    #[arg(short, long, default_value = "1", value_parser = parse_seconds)]
+    time: Duration,
+
+    /// Fail if the estimated exponent of the total time is larger than this, e.g. 1.2 to catch a
+    /// solver that should be linear becoming quadratic
+    #[arg(long)]
+    max_exponent: Option<f64>,
+}
+
+/// The median time of each stage for one input size
+struct Measurement {
+    size: usize,
+    times: StageTimes,
+    runs: usize,
+}
+
+pub fn run(args: &ScaleArgs) -> ExitCode {
+    if args.days.input.is_some() {
+        eprintln!("error: scale generates its own inputs");
+        return ExitCode::FAILURE;
+    }
+    if args.from == 0 || args.from > args.to || args.steps == 0 {
+        eprintln!("error: need at least one step, and sizes with 0 < --from <= --to");
+        return ExitCode::FAILURE;
+    }
+
+    let (day, aoc) = match args.days.days() {
+        Ok(days) => days[0],
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let generator = match REGISTRY.generator(args.days.year, day) {
+        Ok(generator) => generator,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let parts = args.days.parts();
+    let stages = Stage::measured(&parts);
+    let time_budget = args.time;
+
+    print!("{:>8} {:>10}", "size", "bytes");
+    for stage in &stages {
+        print!(" {:>12}", format!("{stage} (us)"));
+    }
+    println!(" {:>8}", "runs");
+
+    let mut measurements = Vec::new();
+    let mut any_failed = false;
+    for size in sizes(args.from, args.to, args.steps) {
+        let input = generator(&mut Rng::new(args.seed), size);
+
+        // One run to warm up, which also finds any errors before measuring
+        if let Err(e) = run_with_timing(aoc, &input, &Params::default(), &parts) {
+            println!("{size:>8} error: {}", e.kind());
+            any_failed = true;
+            continue;
+        }
+
+        let mut samples = Vec::new();
+        let start = Instant::now();
+        while samples.is_empty() || start.elapsed() < time_budget {
+            match run_with_timing(aoc, &input, &Params::default(), &parts) {
+                Ok(solution) => samples.push(solution.times()),
+                Err(e) => unreachable!("failed after the warm up run succeeded: {e}"),
+            }
+        }
+
+        let median = |stage| Stats::new(samples.iter().map(|t| t.get(stage)).collect()).median;
+        let measurement = Measurement {
+            size,
+            times: StageTimes {
+                parse: median(Stage::Parse),
+                pt1: median(Stage::Pt1),
+                pt2: median(Stage::Pt2),
+            },
+            runs: samples.len(),
+        };
+
+        print!("{size:>8} {:>10}", input.len());
+        for &stage in &stages {
+            print!(
+                " {:>12.1}",
+                measurement.times.get(stage).as_secs_f64() * 1e6
+            );
+        }
+        println!(" {:>8}", measurement.runs);
+        measurements.push(measurement);
+    }
+
+    // The exponent k where the time grows as size^k
+    print!("{:>8} {:>10}", "exponent", "");
+    let mut total_exponent = None;
+    for &stage in &stages {
+        let points: Vec<(usize, Duration)> = measurements
+            .iter()
+            .map(|m| (m.size, m.times.get(stage)))
+            .collect();
+        let exponent = exponent(&points);
+        match exponent {
+            Some(exponent) => print!(" {exponent:>12.2}"),
+            None => print!(" {:>12}", "-"),
+        }
+        if stage == Stage::Total {
+            total_exponent = exponent;
+        }
+    }
+    println!();
+
+    if let Some(max) = args.max_exponent {
+        match total_exponent {
+            Some(exponent) if exponent > max => {
+                eprintln!("total time grows as size^{exponent:.2}, more than size^{max}");
+                any_failed = true;
+            }
+            Some(_) => {}
+            None => {
+                eprintln!("error: need at least two sizes to estimate the exponent");
+                any_failed = true;
+            }
+        }
+    }
+
+    if any_failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// `steps` sizes from `from` to `to`, spaced evenly on a log scale
+fn sizes(from: usize, to: usize, steps: usize) -> Vec<usize> {
+    if steps == 1 {
+        return vec![from];
+    }
+    let ratio = (to as f64 / from as f64).powf(1.0 / (steps - 1) as f64);
+    let mut sizes: Vec<usize> = (0..steps)
+        .map(|i| (from as f64 * ratio.powi(i as i32)).round() as usize)
+        .collect();
+    sizes.dedup();
+    sizes
+}
+
+/// Estimate the exponent k, where the time grows as size^k, from the slope of the least squares
+/// line through the points on a log-log scale. Stages that took no time are ignored, and at least
+/// two different sizes are needed.
+fn exponent(points: &[(usize, Duration)]) -> Option<f64> {
+    let logs: Vec<(f64, f64)> = points
+        .iter()
+        .filter(|(_, time)| !time.is_zero())
+        .map(|&(size, time)| ((size as f64).ln(), time.as_secs_f64().ln()))
+        .collect();
+    let n = logs.len() as f64;
+    let mean_x = logs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = logs.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(vec![100, 200, 400, 800, 1600], sizes(100, 1600, 5));
+        assert_eq!(vec![10], sizes(10, 1000, 1));
+        assert_eq!(vec![1, 2], sizes(1, 2, 4));
+    }
+
+    #[test]
+    fn test_exponent() {
+        let quadratic: Vec<(usize, Duration)> = [10, 20, 40, 80]
+            .map(|n| (n, Duration::from_nanos((n * n) as u64)))
+            .to_vec();
+        assert!((exponent(&quadratic).unwrap() - 2.0).abs() < 1e-9);
+
+        let same_size = [
+            (10, Duration::from_micros(1)),
+            (10, Duration::from_micros(2)),
+        ];
+        assert_eq!(None, exponent(&same_size));
+        assert_eq!(None, exponent(&[]));
+    }
+}
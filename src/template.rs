@@ -1,18 +1,21 @@
-use crate::Aoc;
-
-const INPUT: &str = include_str!("../inputs/XX.in");
+use crate::{Answer, Solution, error::AocError};
 
 pub struct DayXX;
 
-impl Aoc for DayXX {
-    fn run(&self) -> (String, String) {
-        let (pt1, pt2) = run_on_input(INPUT);
-        (pt1.to_string(), pt2.to_string())
+impl Solution for DayXX {
+    type Parsed = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, AocError> {
+        Ok(input.lines().map(String::from).collect())
     }
-}
 
-fn run_on_input(input: &str) -> (usize, usize) {
-    (0, 0)
+    fn part1(&self, _lines: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(Answer::NotImplemented)
+    }
+
+    fn part2(&self, _lines: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(Answer::NotImplemented)
+    }
 }
 
 #[cfg(test)]
@@ -23,8 +26,8 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let (pt1, pt2) = run_on_input(EXAMPLE_INPUT);
-        assert_eq!(0, pt1);
-        assert_eq!(0, pt2);
+        let lines = DayXX.parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(Answer::NotImplemented, DayXX.part1(&lines).unwrap());
+        assert_eq!(Answer::NotImplemented, DayXX.part2(&lines).unwrap());
     }
 }
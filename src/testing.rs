@@ -0,0 +1,77 @@
+//! Differential testing of the solvers against slower reference solvers.
+//!
+//! Many solvers rely on assumptions about the puzzle input that hold for the real input but aren't
+//! checked. Each day with a reference solver (registered as the `naive` alternative) has a random
+//! input generator, and the tests here run both solvers on lots of generated inputs to find the
+//! ones where they disagree. There's also a helper for checking the parallel implementations of
+//! the solvers against their single threaded ones.
+
+use std::fmt::Write;
+
+use crate::{Aoc, Part, answers::Params, generate::Rng};
+
+/// Run `solver` and `reference` on `cases` generated inputs, and panic if they give different
+/// answers for any of them. The panic message shows the shortest input they disagree on, along
+/// with its seed and how many inputs disagreed in total.
+pub fn check_against_reference(
+    solver: &dyn Aoc,
+    reference: &dyn Aoc,
+    cases: u64,
+    generate: impl Fn(&mut Rng) -> String,
+) {
+    let mut failures = Vec::new();
+    for seed in 0..cases {
+        let input = generate(&mut Rng::new(seed));
+        let expected = solve(reference, &input);
+        let actual = solve(solver, &input);
+
+        let differences: Vec<String> = expected
+            .iter()
+            .zip(&actual)
+            .zip(["pt1", "pt2"])
+            .filter(|((expected, actual), _)| expected != actual)
+            .map(|((expected, actual), name)| {
+                format!("{name} = {actual}, reference gives {expected}")
+            })
+            .collect();
+        if !differences.is_empty() {
+            failures.push((seed, input, differences));
+        }
+    }
+
+    if let Some((seed, input, differences)) =
+        failures.iter().min_by_key(|(_, input, _)| input.len())
+    {
+        let mut message = format!(
+            "{} of {cases} generated inputs gave different answers to the reference solver, \
+             the shortest is seed {seed}:\n{input}",
+            failures.len()
+        );
+        for difference in differences {
+            writeln!(message, "{difference}").unwrap();
+        }
+        panic!("{message}");
+    }
+}
+
+/// Both answers, or the error, as text so that answers and errors can be compared
+fn solve(aoc: &dyn Aoc, input: &str) -> [String; 2] {
+    let parsed = match aoc.parse_input(input, &Params::default()) {
+        Ok(parsed) => parsed,
+        Err(e) => return [0, 1].map(|_| format!("error: {}", e.kind())),
+    };
+    [Part::One, Part::Two].map(|part| match aoc.solve_part(parsed.as_ref(), part) {
+        Ok(answer) => answer.to_string(),
+        Err(e) => format!("error: {}", e.kind()),
+    })
+}
+
+/// Run `f` in a rayon pool with several threads, so that the parallel implementations really do
+/// split their work between threads, even on a machine with a single CPU
+pub fn with_threads<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap()
+        .install(f)
+}
@@ -0,0 +1,232 @@
+//! Spans for timing the phases inside a solver, e.g. computing distances, sorting and merging.
+//!
+//! A span is timed from [`span`] until the guard it returns is dropped, and spans opened while
+//! another is open are nested inside it. Spans with the same name and parent are merged, so a span
+//! in a loop shows the total time across every iteration and how many there were. Tracing is off
+//! unless [`enable`] is called, and then `span` only checks a flag, so solvers can leave spans in
+//! place. Spans are recorded per thread, so spans opened inside parallel jobs are only recorded on
+//! the thread that runs the day.
+
+use std::{
+    cell::RefCell,
+    fmt::Write,
+    mem,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static RECORDER: RefCell<Recorder> = RefCell::default();
+}
+
+/// Start recording spans, on every thread
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Start a span, which ends when the returned guard is dropped
+#[inline]
+pub fn span(name: &'static str) -> SpanGuard {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return SpanGuard { start: None };
+    }
+    RECORDER.with_borrow_mut(|recorder| recorder.enter(name));
+    SpanGuard {
+        start: Some(Instant::now()),
+    }
+}
+
+/// The spans recorded on this thread since the last call, leaving none recorded
+pub fn take() -> Vec<Span> {
+    RECORDER.with_borrow_mut(|recorder| mem::take(&mut recorder.spans))
+}
+
+#[must_use = "the span ends as soon as the guard is dropped"]
+pub struct SpanGuard {
+    /// When the span started, or `None` if tracing is off
+    start: Option<Instant>,
+}
+
+impl Drop for SpanGuard {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            let elapsed = start.elapsed();
+            RECORDER.with_borrow_mut(|recorder| recorder.exit(elapsed));
+        }
+    }
+}
+
+/// The total time spent in a span, along with the spans nested inside it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub name: &'static str,
+    pub elapsed: Duration,
+    /// How many times the span was entered
+    pub calls: usize,
+    pub children: Vec<Span>,
+}
+
+impl Span {
+    /// Time spent in this span but not in any of its children
+    pub fn self_time(&self) -> Duration {
+        let children: Duration = self.children.iter().map(|child| child.elapsed).sum();
+        self.elapsed.saturating_sub(children)
+    }
+}
+
+/// Add the times and calls of `spans` to `into`, matching spans by name
+pub fn merge(into: &mut Vec<Span>, spans: Vec<Span>) {
+    for span in spans {
+        match into.iter_mut().find(|existing| existing.name == span.name) {
+            Some(existing) => {
+                existing.elapsed += span.elapsed;
+                existing.calls += span.calls;
+                merge(&mut existing.children, span.children);
+            }
+            None => into.push(span),
+        }
+    }
+}
+
+/// The spans as an indented tree, one line per span with its time in microseconds
+pub fn tree(spans: &[Span]) -> String {
+    fn name_width(spans: &[Span], depth: usize) -> usize {
+        spans
+            .iter()
+            .map(|span| (2 * depth + span.name.len()).max(name_width(&span.children, depth + 1)))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn write_spans(out: &mut String, spans: &[Span], depth: usize, width: usize) {
+        for span in spans {
+            let name = format!("{:indent$}{}", "", span.name, indent = 2 * depth);
+            write!(
+                out,
+                "{name:width$} {:>12.1} us",
+                span.elapsed.as_secs_f64() * 1e6
+            )
+            .unwrap();
+            if span.calls > 1 {
+                write!(out, " ({} calls)", span.calls).unwrap();
+            }
+            out.push('\n');
+            write_spans(out, &span.children, depth + 1, width);
+        }
+    }
+
+    let mut out = String::new();
+    write_spans(&mut out, spans, 0, name_width(spans, 0));
+    out
+}
+
+/// The spans recorded on one thread
+#[derive(Default)]
+struct Recorder {
+    spans: Vec<Span>,
+    /// The index of each open span among its siblings, from the outermost in
+    open: Vec<usize>,
+}
+
+impl Recorder {
+    /// The children of the innermost open span
+    fn innermost(&mut self) -> &mut Vec<Span> {
+        let mut spans = &mut self.spans;
+        for &i in &self.open {
+            spans = &mut spans[i].children;
+        }
+        spans
+    }
+
+    fn enter(&mut self, name: &'static str) {
+        let siblings = self.innermost();
+        let index = match siblings.iter().position(|span| span.name == name) {
+            Some(index) => index,
+            None => {
+                siblings.push(Span {
+                    name,
+                    elapsed: Duration::ZERO,
+                    calls: 0,
+                    children: Vec::new(),
+                });
+                siblings.len() - 1
+            }
+        };
+        self.open.push(index);
+    }
+
+    fn exit(&mut self, elapsed: Duration) {
+        let index = self.open.pop().expect("span ended without being started");
+        let span = &mut self.innermost()[index];
+        span.elapsed += elapsed;
+        span.calls += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(name: &'static str, micros: u64, children: Vec<Span>) -> Span {
+        Span {
+            name,
+            elapsed: Duration::from_micros(micros),
+            calls: 1,
+            children,
+        }
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut recorder = Recorder::default();
+        recorder.enter("parse");
+        for _ in 0..3 {
+            recorder.enter("sort");
+            recorder.exit(Duration::from_micros(10));
+        }
+        recorder.exit(Duration::from_micros(50));
+        recorder.enter("pt1");
+        recorder.exit(Duration::from_micros(5));
+
+        let mut sort = span("sort", 30, vec![]);
+        sort.calls = 3;
+        let expected = vec![span("parse", 50, vec![sort]), span("pt1", 5, vec![])];
+        assert_eq!(expected, recorder.spans);
+        assert!(recorder.open.is_empty());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut spans = vec![span("parse", 50, vec![span("sort", 30, vec![])])];
+        merge(
+            &mut spans,
+            vec![
+                span("parse", 40, vec![span("distances", 10, vec![])]),
+                span("pt1", 5, vec![]),
+            ],
+        );
+
+        let children: Vec<&str> = spans[0].children.iter().map(|child| child.name).collect();
+        assert_eq!(vec!["sort", "distances"], children);
+        assert_eq!(Duration::from_micros(90), spans[0].elapsed);
+        assert_eq!(2, spans[0].calls);
+        assert_eq!(Duration::from_micros(50), spans[0].self_time());
+        assert_eq!("pt1", spans[1].name);
+    }
+
+    #[test]
+    fn test_tree() {
+        let mut spans = vec![
+            span("parse", 50, vec![span("sort", 30, vec![])]),
+            span("pt1", 5, vec![]),
+        ];
+        spans[1].calls = 2;
+        assert_eq!(
+            "parse          50.0 us\n  sort         30.0 us\npt1             5.0 us (2 calls)\n",
+            tree(&spans)
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::{fmt::Display, process::ExitCode, str::FromStr};
+
+use crate::{REGISTRY, input::InputArgs};
+
+#[derive(clap::Args)]
+pub struct ValidateArgs {
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+pub fn run(args: &ValidateArgs) -> ExitCode {
+    let args = &args.input;
+    let validator = match REGISTRY.validator(args.year, args.day) {
+        Ok(validator) => validator,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let input = match args.source().read(args.day) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let name = args.name();
+    let violations = validator(&input);
+    for violation in &violations {
+        println!("{name}: {violation}");
+    }
+
+    match violations.len() {
+        0 => {
+            println!("{name}: ok");
+            ExitCode::SUCCESS
+        }
+        1 => {
+            eprintln!("1 problem found");
+            ExitCode::FAILURE
+        }
+        n => {
+            eprintln!("{n} problems found");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Something wrong with an input: either it isn't in the format the parser expects, or it breaks
+/// an assumption the solver makes. Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn at(line: usize, column: usize, message: impl Display) -> Self {
+        Violation {
+            line,
+            column: Some(column),
+            message: message.to_string(),
+        }
+    }
+
+    /// A violation of the line as a whole
+    pub fn line(line: usize, message: impl Display) -> Self {
+        Violation {
+            line,
+            column: None,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {column}: {}", self.line, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+/// The lines of the input with their line numbers, ignoring blank lines at the end
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .trim_end()
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+}
+
+/// The column at which `part`, which must be a slice of `line`, starts
+pub fn column(line: &str, part: &str) -> usize {
+    let offset = part.as_ptr() as usize - line.as_ptr() as usize;
+    line[..offset].chars().count() + 1
+}
+
+/// Check that `part` of `line` (on line number `line_number`) is a whole number that fits in a `T`,
+/// returning it if so. Negative numbers are only accepted if `T` is signed.
+pub fn number<T: FromStr>(
+    violations: &mut Vec<Violation>,
+    line_number: usize,
+    line: &str,
+    part: &str,
+    what: &str,
+) -> Option<T> {
+    let start = column(line, part);
+    let digits = part.strip_prefix('-').unwrap_or(part);
+    let sign = part.len() - digits.len();
+    if digits.is_empty() {
+        violations.push(Violation::at(line_number, start, format!("missing {what}")));
+        return None;
+    }
+    if let Some((i, c)) = digits
+        .chars()
+        .enumerate()
+        .find(|(_, c)| !c.is_ascii_digit())
+    {
+        violations.push(Violation::at(
+            line_number,
+            start + sign + i,
+            format!("unexpected {c:?} in {what} {part:?}"),
+        ));
+        return None;
+    }
+    match part.parse() {
+        Ok(n) => Some(n),
+        Err(_) => {
+            let problem = if sign > 0 && digits.parse::<T>().is_ok() {
+                "can't be negative"
+            } else {
+                "is too large"
+            };
+            violations.push(Violation::at(
+                line_number,
+                start,
+                format!("{what} {part} {problem}"),
+            ));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    use crate::{DEFAULT_YEAR, generate::Rng};
+
+    #[test]
+    fn test_number() {
+        let mut violations = Vec::new();
+        let line = "12,x4,,99999,-3";
+        let parts: Vec<&str> = line.split(',').collect();
+        assert_eq!(
+            Some(12),
+            number::<u8>(&mut violations, 3, line, parts[0], "x")
+        );
+        assert_eq!(None, number::<u8>(&mut violations, 3, line, parts[1], "y"));
+        assert_eq!(None, number::<u8>(&mut violations, 3, line, parts[2], "z"));
+        assert_eq!(None, number::<u16>(&mut violations, 3, line, parts[3], "w"));
+        assert_eq!(None, number::<u16>(&mut violations, 3, line, parts[4], "v"));
+        assert_eq!(
+            Some(-3),
+            number::<i16>(&mut violations, 3, line, parts[4], "v")
+        );
+        assert_eq!(
+            vec![
+                "line 3, column 4: unexpected 'x' in y \"x4\"",
+                "line 3, column 7: missing z",
+                "line 3, column 8: w 99999 is too large",
+                "line 3, column 14: v -3 can't be negative",
+            ],
+            violations
+                .iter()
+                .map(|violation| violation.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// The examples and generated inputs are all in the right format, and meet the solvers'
+    /// assumptions, for the days that have a validator
+    #[test]
+    fn test_valid_inputs() {
+        let inputs_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        for year in REGISTRY.years() {
+            for day in REGISTRY.days(year) {
+                let Ok(validator) = REGISTRY.validator(year, day) else {
+                    continue;
+                };
+                let mut example = inputs_dir.clone();
+                if year != DEFAULT_YEAR {
+                    example.push(year.to_string());
+                }
+                let example =
+                    std::fs::read_to_string(example.join(format!("{day:02}.ex"))).unwrap();
+                assert_eq!(
+                    Vec::<Violation>::new(),
+                    validator(&example),
+                    "day {day} of {year} example"
+                );
+
+                let Ok(generator) = REGISTRY.generator(year, day) else {
+                    continue;
+                };
+                for seed in 0..5 {
+                    let input = generator(&mut Rng::new(seed), 50);
+                    assert_eq!(
+                        Vec::<Violation>::new(),
+                        validator(&input),
+                        "day {day} of {year} seed {seed}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use crate::{REGISTRY, input::InputArgs};
+
+#[derive(clap::Args)]
+pub struct VisualizeArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Also write the drawing to this file as an SVG
+    #[arg(long)]
+    svg: Option<PathBuf>,
+}
+
+/// A drawing of how a day solves an input
+pub struct Visualization {
+    /// Plain text, for printing in the terminal
+    pub text: String,
+    pub svg: String,
+}
+
+pub fn run(args: &VisualizeArgs) -> ExitCode {
+    let input_args = &args.input;
+    let visualizer = match REGISTRY.visualizer(input_args.year, input_args.day) {
+        Ok(visualizer) => visualizer,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let input = match input_args.source().read(input_args.day) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let visualization = match visualizer(&input) {
+        Ok(visualization) => visualization,
+        Err(e) => {
+            eprintln!("error: {}", e.for_day(input_args.day));
+            return ExitCode::FAILURE;
+        }
+    };
+    print!("{}", visualization.text);
+
+    if let Some(path) = &args.svg {
+        if let Err(e) = fs::write(path, visualization.svg) {
+            eprintln!("error: could not write {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+        eprintln!("drawing written to {}", path.display());
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,183 @@
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::{DayArgs, TimedSolution, input::InputSource, run_day};
+
+#[derive(clap::Args)]
+#[command(mut_arg("day", |day| day.required(true).help("The day to run")))]
+pub struct WatchArgs {
+    #[command(flatten)]
+    pub days: DayArgs,
+
+    /// How often to check the files for changes, in milliseconds
+    #[arg(long, default_value_t = 500)]
+    interval: u64,
+}
+
+/// One of the inputs that is re-run on every change
+struct Target {
+    label: &'static str,
+    source: InputSource,
+    previous: Option<TimedSolution>,
+}
+
+pub fn run(args: &WatchArgs) -> ExitCode {
+    let (day, aoc) = match args.days.days() {
+        Ok(days) => days[0],
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let parts = args.days.parts();
+
+    let source = args.days.source();
+    if matches!(source, InputSource::Stdin) {
+        eprintln!("error: can't watch stdin for changes");
+        return ExitCode::FAILURE;
+    }
+    let example = InputSource::File(args.days.inputs_dir().join(format!("{day:02}.ex")));
+
+    let mut targets = [
+        Target {
+            label: "example",
+            source: example,
+            previous: None,
+        },
+        Target {
+            label: "input",
+            source,
+            previous: None,
+        },
+    ];
+
+    // Changes to the answers are picked up too, so that recording new answers re-checks them
+    let watched: Vec<PathBuf> = targets
+        .iter()
+        .flat_map(|target| {
+            [
+                target.source.path_for_day(day),
+                target.source.answers_path(day),
+            ]
+        })
+        .flatten()
+        .collect();
+
+    println!(
+        "watching {}",
+        watched
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let mut last_seen = None;
+    loop {
+        let stamps: Vec<_> = watched.iter().map(|path| modified(path)).collect();
+        if last_seen.as_ref() != Some(&stamps) {
+            if last_seen.is_some() {
+                println!();
+            }
+            last_seen = Some(stamps);
+
+            for target in &mut targets {
+                let label = format!("[{}]", target.label);
+                match run_day(day, aoc, &target.source, &parts, false) {
+                    Ok(solution) => {
+                        println!("{label:9} {solution}");
+                        if let Some(previous) = &target.previous {
+                            println!("{:9} {}", "", compare(previous, &solution));
+                        }
+                        for mismatch in solution.mismatches() {
+                            println!("{:9} wrong answer: {mismatch}", "");
+                        }
+                        target.previous = Some(solution);
+                    }
+                    Err(e) => println!("{label:9} error: {}", e.kind()),
+                }
+            }
+        }
+
+        thread::sleep(Duration::from_millis(args.interval));
+    }
+}
+
+/// When the file was last modified and its length, or None if it doesn't exist (yet)
+fn modified(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Describe how the answers and total time have changed since the previous run
+fn compare(previous: &TimedSolution, current: &TimedSolution) -> String {
+    let changed: Vec<String> = [
+        ("pt1", &previous.pt1, &current.pt1),
+        ("pt2", &previous.pt2, &current.pt2),
+    ]
+    .into_iter()
+    .filter_map(|(name, previous, current)| match (previous, current) {
+        (Some(previous), Some(current)) if previous.answer != current.answer => {
+            Some(format!("{name} changed from {}", previous.answer))
+        }
+        _ => None,
+    })
+    .collect();
+
+    let answers = if changed.is_empty() {
+        "answers unchanged".to_string()
+    } else {
+        changed.join(", ")
+    };
+
+    let before = previous.times().total();
+    let after = current.times().total();
+    let mut description = format!(
+        "{answers}, elapsed {} us -> {} us",
+        before.as_micros(),
+        after.as_micros()
+    );
+    if !before.is_zero() {
+        let change = (after.as_secs_f64() - before.as_secs_f64()) / before.as_secs_f64() * 100.0;
+        description += &format!(" ({change:+.1}%)");
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TimedAnswer, answers::Verification};
+
+    fn solution(pt1: u64, pt2: u64, elapsed_us: u64) -> TimedSolution {
+        let timed = |answer: u64| {
+            Some(TimedAnswer {
+                answer: answer.into(),
+                elapsed: Duration::from_micros(elapsed_us / 2),
+                verification: Verification::Unknown,
+            })
+        };
+        TimedSolution {
+            parse: Duration::ZERO,
+            pt1: timed(pt1),
+            pt2: timed(pt2),
+            usage: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(
+            "answers unchanged, elapsed 100 us -> 150 us (+50.0%)",
+            compare(&solution(1, 2, 100), &solution(1, 2, 150))
+        );
+        assert_eq!(
+            "pt2 changed from 2, elapsed 100 us -> 50 us (-50.0%)",
+            compare(&solution(1, 2, 100), &solution(1, 3, 50))
+        );
+    }
+}